@@ -0,0 +1,60 @@
+use crate::FrameBuffer;
+use crate::color::Color;
+use crate::tiles::Tile;
+
+/// Running per-pixel radiance sums for progressive rendering.
+pub struct Accumulator {
+    sum: Vec<Color>,
+    width: usize,
+    height: usize,
+    passes: u32,
+}
+
+impl Accumulator {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            sum: vec![Color::ZERO; width * height],
+            width,
+            height,
+            passes: 0,
+        }
+    }
+
+    /// Number of completed sample passes.
+    pub fn passes(&self) -> u32 {
+        self.passes
+    }
+
+    /// Adds the radiance of `tile`'s pixels, given row by row.
+    pub fn add_tile(&mut self, tile: &Tile, pixels: &[Color]) {
+        for (row, colors) in pixels.chunks_exact(tile.width).enumerate() {
+            let start = (tile.y + row) * self.width + tile.x;
+            for (acc, color) in self.sum[start..start + tile.width].iter_mut().zip(colors) {
+                *acc += *color;
+            }
+        }
+    }
+
+    pub fn finish_pass(&mut self) {
+        self.passes += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.sum.fill(Color::ZERO);
+        self.passes = 0;
+    }
+
+    /// Writes the running average radiance into `fb`.
+    pub fn resolve(&self, fb: &mut FrameBuffer) {
+        if self.passes == 0 {
+            return;
+        }
+
+        let scale = 1.0 / self.passes as f32;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                fb.set_color(x, y, &(self.sum[y * self.width + x] * scale));
+            }
+        }
+    }
+}
@@ -39,29 +39,29 @@ impl<'a, T> App<'a, T> {
 
     pub fn set_fps(&mut self, fps: usize) -> &mut Self {
         self.window.set_target_fps(fps);
-        return self;
+        self
     }
 
     pub fn set_callback(&mut self, cb: RenderCallback<T>) -> &mut Self {
         self.cb = Some(cb);
-        return self;
+        self
     }
 
     pub fn on_init(&mut self, on_init: RenderCallback<T>) -> &mut Self {
         self.on_init = Some(on_init);
-        return self;
+        self
     }
 
     pub fn run(&mut self) {
         if let Some(on_init) = self.on_init.take() {
-            on_init(&mut self.buffer, &self.window, &mut self.state);
+            on_init(&mut self.buffer, &self.window, self.state);
         }
 
         while self.window.is_open() && !self.window.is_key_down(minifb::Key::Escape) {
             if let Some(cb) = self.cb {
-                (cb)(&mut self.buffer, &self.window, &mut self.state);
+                (cb)(&mut self.buffer, &self.window, self.state);
                 self.window
-                    .update_with_buffer(&self.buffer.buffer(), self.width, self.height)
+                    .update_with_buffer(self.buffer.buffer(), self.width, self.height)
                     .unwrap();
             }
         }
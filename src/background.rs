@@ -0,0 +1,35 @@
+pub mod environment;
+pub mod gradient;
+pub mod sky;
+pub mod solid;
+
+pub use environment::EnvironmentMap;
+pub use gradient::GradientBackground;
+pub use sky::{SkyBackground, SkyConfig};
+pub use solid::SolidBackground;
+
+use crate::{
+    color::Color,
+    math::{Ray, Vector3},
+};
+
+/// Radiance seen by rays that escape the scene without hitting anything.
+pub trait Background: Send + Sync {
+    fn color(&self, ray: &Ray) -> Color;
+
+    /// Whether [`Background::random`] is used to sample the background as a
+    /// light at every diffuse bounce.
+    fn importance_sampled(&self) -> bool {
+        false
+    }
+
+    /// Solid angle density of [`Background::random`] choosing `direction`.
+    fn pdf_value(&self, _direction: &Vector3) -> f32 {
+        0.0
+    }
+
+    /// Direction sampled roughly proportionally to the background's radiance.
+    fn random(&self) -> Vector3 {
+        Vector3::Y
+    }
+}
@@ -0,0 +1,103 @@
+use std::f32::consts::PI;
+
+use glam::Vec2;
+
+use crate::color::{Color, luminance};
+use crate::math::{Distribution2D, Ray, Vector3, rand};
+use crate::textures::ImageTexture;
+
+use super::Background;
+
+/// Equirectangular environment map lighting the scene from every direction.
+///
+/// The top row of the image is straight up (+Y), and directions are importance
+/// sampled proportionally to the luminance of the pixels.
+pub struct EnvironmentMap {
+    image: ImageTexture,
+    /// Rotation about the Y axis in radians.
+    rotation: f32,
+    intensity: f32,
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    /// `rotation` turns the map about the Y axis in degrees, `intensity`
+    /// scales its radiance.
+    pub fn new(image: ImageTexture, rotation: f32, intensity: f32) -> Self {
+        let (width, height) = (image.width(), image.height());
+
+        // Rows near the poles cover less solid angle.
+        let mut func = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let sin_theta = (PI * (y as f32 + 0.5) / height as f32).sin();
+            func.extend((0..width).map(|x| luminance(image.pixel(x, y)).max(0.0) * sin_theta));
+        }
+
+        Self {
+            distribution: Distribution2D::new(&func, width, height),
+            image,
+            rotation: rotation.to_radians(),
+            intensity,
+        }
+    }
+
+    fn uv(&self, direction: &Vector3) -> Vec2 {
+        let d = direction.normalize();
+        let phi = (-d.z).atan2(d.x) + PI + self.rotation;
+        // Unlike `acos(d.y)`, this keeps its precision next to the poles.
+        let theta = d.x.hypot(d.z).atan2(d.y);
+
+        Vec2::new((phi / (2.0 * PI)).rem_euclid(1.0), theta / PI)
+    }
+
+    fn direction(&self, uv: Vec2) -> Vector3 {
+        let phi = 2.0 * PI * uv.x - self.rotation;
+        let theta = PI * uv.y;
+        let sin_theta = theta.sin();
+
+        Vector3::new(-sin_theta * phi.cos(), theta.cos(), sin_theta * phi.sin())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.image.width() == 0 || self.image.height() == 0
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn color(&self, ray: &Ray) -> Color {
+        if self.is_empty() {
+            return Color::ZERO;
+        }
+
+        let uv = self.uv(ray.dir());
+        let (width, height) = (self.image.width(), self.image.height());
+        let x = ((uv.x * width as f32) as usize).min(width - 1);
+        let y = ((uv.y * height as f32) as usize).min(height - 1);
+
+        self.intensity * self.image.pixel(x, y)
+    }
+
+    fn importance_sampled(&self) -> bool {
+        !self.is_empty()
+    }
+
+    /// Image-space density divided by the `2π² sin θ` area of the mapping.
+    fn pdf_value(&self, direction: &Vector3) -> f32 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let uv = self.uv(direction);
+        let sin_theta = (PI * uv.y).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+
+        self.distribution.pdf(uv) / (2.0 * PI * PI * sin_theta)
+    }
+
+    fn random(&self) -> Vector3 {
+        let (uv, _) = self.distribution.sample(rand(), rand());
+        self.direction(uv)
+    }
+}
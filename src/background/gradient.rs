@@ -0,0 +1,29 @@
+use crate::color::Color;
+use crate::math::Ray;
+
+use super::Background;
+
+/// Vertical blend from `horizon` for downward rays to `zenith` for upward rays.
+pub struct GradientBackground {
+    horizon: Color,
+    zenith: Color,
+}
+
+impl GradientBackground {
+    pub fn new(horizon: Color, zenith: Color) -> Self {
+        Self { horizon, zenith }
+    }
+
+    /// White-to-`sky_color` blend.
+    pub fn sky(sky_color: Color) -> Self {
+        Self::new(Color::new(1.0, 1.0, 1.0), sky_color)
+    }
+}
+
+impl Background for GradientBackground {
+    fn color(&self, ray: &Ray) -> Color {
+        let unit = ray.dir().normalize();
+        let a = 0.5 * (unit.y + 1.0);
+        (1.0 - a) * self.horizon + a * self.zenith
+    }
+}
@@ -0,0 +1,260 @@
+use std::f32::consts::PI;
+
+use crate::color::{Color, ColorSpace, xyz_to_srgb};
+use crate::math::{Onb, Ray, Vector3, rand, rand_unit_vec3};
+
+use super::Background;
+
+/// Sky luminance is in kcd/m², scaled so a sunlit white surface at midday
+/// is around 1.
+const SKY_SCALE: f32 = 0.04;
+
+/// Irradiance of the sun before atmospheric extinction, in the same units.
+const SUN_IRRADIANCE: f32 = 6.0;
+
+/// Probability of sampling the sun disk rather than the whole sphere.
+const SUN_SAMPLE_PROBABILITY: f32 = 0.5;
+
+pub struct SkyConfig {
+    /// Degrees above the horizon.
+    pub sun_elevation: f32,
+    /// Degrees from -Z towards +X.
+    pub sun_azimuth: f32,
+    /// Haziness of the atmosphere, from 2 (clear) to about 10 (hazy).
+    pub turbidity: f32,
+    /// Angular diameter of the sun disk in degrees, 0.53 for the real sun.
+    /// Larger disks keep the same irradiance and give softer shadows.
+    pub sun_size: f32,
+    /// Scales the sun's radiance, zero leaves only the sky.
+    pub sun_intensity: f32,
+}
+
+impl Default for SkyConfig {
+    fn default() -> Self {
+        Self {
+            sun_elevation: 45.0,
+            sun_azimuth: 0.0,
+            turbidity: 3.0,
+            sun_size: 0.53,
+            sun_intensity: 1.0,
+        }
+    }
+}
+
+/// Preetham et al.'s analytic daylight sky, with a sun disk that is sampled
+/// directly as a light.
+///
+/// Below the horizon the horizon's color is held.
+pub struct SkyBackground {
+    space: ColorSpace,
+    sun_direction: Vector3,
+    sun_frame: Onb,
+    sun_cos_radius: f32,
+    sun_radiance: Color,
+    /// Perez coefficients for Y, x and y.
+    perez: [Perez; 3],
+    /// Zenith values of Y, x and y divided by the Perez function at the zenith.
+    zenith: [f32; 3],
+}
+
+impl SkyBackground {
+    /// Computes the sky in linear sRGB and converts it to `space`.
+    pub fn new(config: &SkyConfig, space: ColorSpace) -> Self {
+        let elevation = config.sun_elevation.to_radians();
+        let azimuth = config.sun_azimuth.to_radians();
+        let sun_direction = Vector3::new(
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            -elevation.cos() * azimuth.cos(),
+        );
+
+        let t = config.turbidity.max(1.0);
+        let theta_s = (PI / 2.0 - elevation).clamp(0.0, PI / 2.0);
+        let perez = Perez::coefficients(t);
+        let zenith_values = zenith(t, theta_s);
+        let zenith = [0, 1, 2].map(|i| zenith_values[i] / perez[i].eval(0.0, theta_s));
+
+        let radius = (0.5 * config.sun_size).to_radians().max(1e-4);
+        let sun_cos_radius = radius.cos();
+        let solid_angle = 2.0 * PI * (1.0 - sun_cos_radius);
+        let sun_radiance = if sun_direction.y > 0.0 {
+            config.sun_intensity * SUN_IRRADIANCE * sun_transmittance(t, theta_s) / solid_angle
+        } else {
+            Color::ZERO
+        };
+
+        Self {
+            space,
+            sun_direction,
+            sun_frame: Onb::new(&sun_direction),
+            sun_cos_radius,
+            sun_radiance: space.from_srgb(sun_radiance),
+            perez,
+            zenith,
+        }
+    }
+
+    fn sky(&self, direction: &Vector3) -> Color {
+        // Keep away from cos θ = 0, where the Perez function blows up.
+        let cos_theta = direction.y.max(0.01);
+        let theta = cos_theta.acos();
+        let gamma = direction.dot(self.sun_direction).clamp(-1.0, 1.0).acos();
+
+        let [luminance, x, y] =
+            [0, 1, 2].map(|i| self.zenith[i] * self.perez[i].eval(theta, gamma));
+        let luminance = SKY_SCALE * luminance;
+        let xyz = Color::new(x / y * luminance, luminance, (1.0 - x - y) / y * luminance);
+
+        self.space.from_srgb(xyz_to_srgb(xyz).max(Color::ZERO))
+    }
+
+    fn sun_sampled(&self) -> bool {
+        self.sun_radiance != Color::ZERO
+    }
+
+    fn sun_pdf(&self) -> f32 {
+        1.0 / (2.0 * PI * (1.0 - self.sun_cos_radius))
+    }
+}
+
+impl Background for SkyBackground {
+    fn color(&self, ray: &Ray) -> Color {
+        let direction = ray.dir().normalize();
+        let sky = self.sky(&direction);
+        if direction.dot(self.sun_direction) >= self.sun_cos_radius {
+            sky + self.sun_radiance
+        } else {
+            sky
+        }
+    }
+
+    fn importance_sampled(&self) -> bool {
+        true
+    }
+
+    /// Mixture of the sun cone and the uniform sphere.
+    fn pdf_value(&self, direction: &Vector3) -> f32 {
+        let sphere = 1.0 / (4.0 * PI);
+        if !self.sun_sampled() {
+            return sphere;
+        }
+
+        let in_sun = direction.normalize().dot(self.sun_direction) >= self.sun_cos_radius;
+        let sun = if in_sun { self.sun_pdf() } else { 0.0 };
+        SUN_SAMPLE_PROBABILITY * sun + (1.0 - SUN_SAMPLE_PROBABILITY) * sphere
+    }
+
+    fn random(&self) -> Vector3 {
+        if !self.sun_sampled() || rand() >= SUN_SAMPLE_PROBABILITY {
+            return rand_unit_vec3();
+        }
+
+        let z = 1.0 - rand() * (1.0 - self.sun_cos_radius);
+        let phi = 2.0 * PI * rand();
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+        self.sun_frame.local(&Vector3::new(
+            phi.cos() * sin_theta,
+            phi.sin() * sin_theta,
+            z,
+        ))
+    }
+}
+
+/// Perez sky distribution `(1 + A e^(B / cos θ)) (1 + C e^(D γ) + E cos² γ)`.
+#[derive(Clone, Copy)]
+struct Perez {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+}
+
+impl Perez {
+    /// Coefficients for luminance Y and chromaticities x and y.
+    fn coefficients(t: f32) -> [Perez; 3] {
+        let perez = |[a, b, c, d, e]: [[f32; 2]; 5]| Perez {
+            a: a[0] * t + a[1],
+            b: b[0] * t + b[1],
+            c: c[0] * t + c[1],
+            d: d[0] * t + d[1],
+            e: e[0] * t + e[1],
+        };
+
+        [
+            perez([
+                [0.1787, -1.463],
+                [-0.3554, 0.4275],
+                [-0.0227, 5.3251],
+                [0.1206, -2.5771],
+                [-0.067, 0.3703],
+            ]),
+            perez([
+                [-0.0193, -0.2592],
+                [-0.0665, 0.0008],
+                [-0.0004, 0.2125],
+                [-0.0641, -0.8989],
+                [-0.0033, 0.0452],
+            ]),
+            perez([
+                [-0.0167, -0.2608],
+                [-0.095, 0.0092],
+                [-0.0079, 0.2102],
+                [-0.0441, -1.6537],
+                [-0.0109, 0.0529],
+            ]),
+        ]
+    }
+
+    fn eval(&self, theta: f32, gamma: f32) -> f32 {
+        let cos_gamma = gamma.cos();
+        (1.0 + self.a * (self.b / theta.cos()).exp())
+            * (1.0 + self.c * (self.d * gamma).exp() + self.e * cos_gamma * cos_gamma)
+    }
+}
+
+/// Zenith luminance (kcd/m²) and chromaticity for turbidity `t` and sun zenith
+/// angle `theta_s`.
+fn zenith(t: f32, theta_s: f32) -> [f32; 3] {
+    let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+    let luminance = (4.0453 * t - 4.971) * chi.tan() - 0.2155 * t + 2.4192;
+
+    let th = Vector3::new(theta_s.powi(3), theta_s * theta_s, theta_s);
+    let chromaticity = |t2: [f32; 4], t1: [f32; 4], t0: [f32; 4]| {
+        let poly = |c: [f32; 4]| th.dot(Vector3::new(c[0], c[1], c[2])) + c[3];
+        t * t * poly(t2) + t * poly(t1) + poly(t0)
+    };
+    let x = chromaticity(
+        [0.00166, -0.00375, 0.00209, 0.0],
+        [-0.02903, 0.06377, -0.03202, 0.00394],
+        [0.11693, -0.21196, 0.06052, 0.25886],
+    );
+    let y = chromaticity(
+        [0.00275, -0.0061, 0.00317, 0.0],
+        [-0.04214, 0.0897, -0.04153, 0.00516],
+        [0.15346, -0.26756, 0.0667, 0.26688],
+    );
+
+    [luminance.max(0.0), x, y]
+}
+
+/// Rayleigh and aerosol extinction of sunlight through the atmosphere,
+/// evaluated at representative wavelengths of the red, green and blue channels.
+fn sun_transmittance(t: f32, theta_s: f32) -> Color {
+    let wavelengths = Color::new(0.65, 0.57, 0.475); // micrometers
+    let degrees = theta_s.to_degrees();
+    let air_mass = 1.0 / (theta_s.cos() + 0.15 * (93.885 - degrees).powf(-1.253));
+
+    let beta = 0.04608 * t - 0.04586;
+    let channel = |lambda: f32| {
+        let rayleigh = 0.008735 * lambda.powf(-4.08);
+        let aerosol = beta * lambda.powf(-1.3);
+        (-air_mass * (rayleigh + aerosol)).exp()
+    };
+
+    Color::new(
+        channel(wavelengths.x),
+        channel(wavelengths.y),
+        channel(wavelengths.z),
+    )
+}
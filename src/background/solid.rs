@@ -0,0 +1,25 @@
+use crate::color::Color;
+use crate::math::Ray;
+
+use super::Background;
+
+pub struct SolidBackground {
+    color: Color,
+}
+
+impl SolidBackground {
+    pub fn new(color: Color) -> Self {
+        Self { color }
+    }
+
+    /// Pure black background, leaving emissive materials as the only light.
+    pub fn black() -> Self {
+        Self::new(Color::ZERO)
+    }
+}
+
+impl Background for SolidBackground {
+    fn color(&self, _ray: &Ray) -> Color {
+        self.color
+    }
+}
@@ -23,18 +23,18 @@ impl Buffer {
 
     pub fn clear(&mut self, color: u32) -> &Self {
         self.buffer.fill(color);
-        return self;
+        self
     }
 
     pub fn set_color(&mut self, x: usize, y: usize, color: &Color) -> &Self {
-        self.set(x, y, vec3_to_color(&color));
-        return self;
+        self.set(x, y, vec3_to_color(color));
+        self
     }
 
     fn set(&mut self, x: usize, y: usize, color: u32) -> &Self {
         let idx = y * self.width + x;
         self.buffer[idx] = color;
-        return self;
+        self
     }
 
     pub fn draw_rect(
@@ -65,7 +65,7 @@ impl Buffer {
                 self.set(i, j, color);
             }
         }
-        return self;
+        self
     }
 
     pub fn buffer(&self) -> &[u32] {
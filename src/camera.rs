@@ -1,11 +1,23 @@
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
 use crate::{
+    background::Background,
     color::Color,
-    math::{Point3, Vector3, degrees_to_radians, rand, vec3_rand_in_unit_disk},
+    lights::Light,
+    math::{
+        Point3, Vector3, degrees_to_radians, rand, sample_seed, seed_rng, vec3_rand_in_unit_disk,
+    },
+    tiles::{Tile, TileOrder, tiles},
 };
 
 use crate::{
-    Buffer,
-    hittable::HittableList,
+    FrameBuffer,
+    accumulator::Accumulator,
+    hittable::{HitRecord, Hittable, HittableList},
     math::{Interval, Ray},
 };
 
@@ -20,12 +32,20 @@ pub struct Camera {
     pixel_samples_scale: f32,
     max_ray_depth: i32,
     light_intensity: f32,
-    sky_color: Color,
+    background: Arc<dyn Background>,
+    delta_lights: Vec<Arc<dyn Light>>,
     defocus_angle: f32,
     defocus_disk_u: Vector3,
     defocus_disk_v: Vector3,
+    shutter_open: f32,
+    shutter_close: f32,
+    threads: usize,
+    tile_size: usize,
+    tile_order: TileOrder,
+    seed: u64,
 }
 
+#[derive(Clone)]
 pub struct CameraConfig {
     pub image_width: u32,
     pub image_height: u32,
@@ -33,12 +53,26 @@ pub struct CameraConfig {
     pub samples_per_pixel: u32,
     pub max_ray_depth: i32,
     pub light_intensity: f32,
-    pub sky_color: Color,
+    /// Radiance returned by rays that miss every object.
+    pub background: Arc<dyn Background>,
+    /// Point, spot and directional lights, which rays can't hit.
+    pub delta_lights: Vec<Arc<dyn Light>>,
     pub fov: f32,
     pub look_from: Point3,
     pub look_at: Point3,
     pub defocus_angle: f32,
     pub focus_dist: f32,
+    /// Ray times are spread uniformly between the shutter open and close
+    /// times, moving objects blur across the interval.
+    pub shutter_open: f32,
+    pub shutter_close: f32,
+    /// Number of render threads, `0` uses every available core.
+    pub threads: usize,
+    /// Edge length in pixels of the square tiles the image is split into.
+    pub tile_size: usize,
+    pub tile_order: TileOrder,
+    /// Base seed of the per-sample random sequences.
+    pub seed: u64,
 }
 
 impl Camera {
@@ -82,27 +116,166 @@ impl Camera {
             pixel_samples_scale: 1.0 / config.samples_per_pixel as f32,
             max_ray_depth: config.max_ray_depth,
             light_intensity: config.light_intensity,
-            sky_color: config.sky_color,
+            background: Arc::clone(&config.background),
+            delta_lights: config.delta_lights.clone(),
             defocus_angle: config.defocus_angle,
             defocus_disk_u,
             defocus_disk_v,
+            shutter_open: config.shutter_open,
+            shutter_close: config.shutter_close,
+            threads: config.threads,
+            tile_size: config.tile_size,
+            tile_order: config.tile_order,
+            seed: config.seed,
         }
     }
 
-    pub fn render(&self, bf: &mut Buffer, world: &HittableList) {
-        for y in 0..self.image_height as usize {
-            for x in 0..self.image_width as usize {
+    pub fn image_width(&self) -> u32 {
+        self.image_width
+    }
+
+    pub fn image_height(&self) -> u32 {
+        self.image_height
+    }
+
+    pub fn samples_per_pixel(&self) -> u32 {
+        self.samples_per_pixel
+    }
+
+    /// Tiles of the image in the configured size and order.
+    pub fn tiles(&self) -> Vec<Tile> {
+        tiles(
+            self.image_width as usize,
+            self.image_height as usize,
+            self.tile_size,
+            self.tile_order,
+        )
+    }
+
+    /// Renders `world` into `fb`. Objects in `lights` are sampled directly at
+    /// every diffuse bounce, they must also be part of `world` to be visible.
+    pub fn render(&self, fb: &mut FrameBuffer, world: &dyn Hittable, lights: &HittableList) {
+        self.render_region(fb, &self.tiles(), world, lights);
+    }
+
+    /// Renders only `tiles` into `fb`, with every sample per pixel.
+    pub fn render_region(
+        &self,
+        fb: &mut FrameBuffer,
+        tiles: &[Tile],
+        world: &dyn Hittable,
+        lights: &HittableList,
+    ) {
+        self.render_tiles(
+            tiles,
+            world,
+            lights,
+            0..self.samples_per_pixel,
+            |tile, pixels| {
+                for (i, pixel_sum) in pixels.iter().enumerate() {
+                    let (x, y) = (tile.x + i % tile.width, tile.y + i / tile.width);
+                    fb.set_color(x, y, &(pixel_sum * self.pixel_samples_scale));
+                }
+            },
+        );
+    }
+
+    /// Adds one more sample per pixel to `accum`.
+    ///
+    /// Pass `n` uses the same random sequences as sample `n` of [`Camera::render`],
+    /// so accumulating `samples_per_pixel` passes gives the same image.
+    pub fn render_pass(
+        &self,
+        accum: &mut Accumulator,
+        world: &dyn Hittable,
+        lights: &HittableList,
+    ) {
+        let pass = accum.passes();
+        self.render_tiles(
+            &self.tiles(),
+            world,
+            lights,
+            pass..pass + 1,
+            |tile, pixels| accum.add_tile(tile, &pixels),
+        );
+        accum.finish_pass();
+    }
+
+    /// Renders tiles in parallel, taking them in the order given, and hands
+    /// each tile's radiance summed over `samples` to `sink` on the calling
+    /// thread as it completes.
+    fn render_tiles<F>(
+        &self,
+        tiles: &[Tile],
+        world: &dyn Hittable,
+        lights: &HittableList,
+        samples: Range<u32>,
+        mut sink: F,
+    ) where
+        F: FnMut(&Tile, Vec<Color>),
+    {
+        let next_tile = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel();
+
+        thread::scope(|s| {
+            for _ in 0..self.thread_count().min(tiles.len()) {
+                let tx = tx.clone();
+                let next_tile = &next_tile;
+                let samples = samples.clone();
+
+                s.spawn(move || {
+                    while let Some(tile) = tiles.get(next_tile.fetch_add(1, Ordering::Relaxed)) {
+                        let pixels = self.render_tile(tile, world, lights, &samples);
+                        if tx.send((tile, pixels)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+
+            for (tile, pixels) in rx {
+                sink(tile, pixels);
+            }
+        });
+    }
+
+    /// Radiance of each pixel of `tile` summed over `samples`, row by row.
+    fn render_tile(
+        &self,
+        tile: &Tile,
+        world: &dyn Hittable,
+        lights: &HittableList,
+        samples: &Range<u32>,
+    ) -> Vec<Color> {
+        let width = self.image_width as usize;
+        let mut pixels = Vec::with_capacity(tile.width * tile.height);
+
+        for y in tile.y..tile.y + tile.height {
+            for x in tile.x..tile.x + tile.width {
+                let pixel = (y * width + x) as u64;
                 let mut pixel_color = Color::new(0.0, 0.0, 0.0);
 
-                for _ in 0..self.samples_per_pixel {
+                for sample in samples.clone() {
+                    seed_rng(sample_seed(self.seed, pixel, sample as u64));
                     let r = self.get_ray(x, y);
-                    pixel_color += self.ray_color(&r, self.max_ray_depth, &world);
+                    pixel_color += self.ray_color(r, world, lights);
                 }
 
-                let pixel_color = pixel_color * self.pixel_samples_scale;
-                bf.set_color(x, y, &pixel_color);
+                pixels.push(pixel_color);
             }
         }
+
+        pixels
+    }
+
+    /// Number of render threads in use.
+    pub fn thread_count(&self) -> usize {
+        if self.threads > 0 {
+            return self.threads;
+        }
+
+        thread::available_parallelism().map_or(1, |n| n.get())
     }
 
     fn get_ray(&self, x: usize, y: usize) -> Ray {
@@ -117,26 +290,133 @@ impl Camera {
             self.defocus_disk_sample()
         };
 
-        Ray::new(ray_orig, pixel_sample - ray_orig)
+        let time = self.shutter_open + rand() * (self.shutter_close - self.shutter_open);
+
+        Ray::with_time(ray_orig, pixel_sample - ray_orig, time)
     }
 
-    fn ray_color(&self, ray: &Ray, depth: i32, world: &HittableList) -> Color {
-        if depth < 0 {
-            return Vector3::new(0.0, 0.0, 0.0);
+    /// Path traces `ray`, combining light sampling and BSDF sampling with
+    /// multiple importance sampling at non-specular bounces.
+    fn ray_color(&self, mut ray: Ray, world: &dyn Hittable, lights: &HittableList) -> Color {
+        let mut radiance = Color::ZERO;
+        let mut throughput = Color::ONE;
+        // Density of the BSDF sample that produced `ray`, `None` for camera
+        // rays and specular bounces, which light sampling can't produce.
+        let mut bsdf_pdf: Option<f32> = None;
+
+        for _ in 0..=self.max_ray_depth {
+            let weight = |bsdf_pdf: Option<f32>| match bsdf_pdf {
+                Some(pdf) => power_heuristic(pdf, self.light_pdf(lights, ray.orig(), ray.dir())),
+                None => 1.0,
+            };
+
+            let Some(hit) = world.hit(&ray, &Interval::new(0.001, f32::INFINITY)) else {
+                radiance += throughput * self.background.color(&ray) * weight(bsdf_pdf);
+                break;
+            };
+
+            let emitted = hit.mat().emitted(&hit);
+            if emitted != Color::ZERO {
+                radiance += throughput * emitted * weight(bsdf_pdf);
+            }
+
+            let Some(scatter) = hit.mat().scatter(&ray, &hit) else {
+                break;
+            };
+
+            if scatter.pdf.is_some() && (!lights.is_empty() || self.background.importance_sampled())
+            {
+                let direct = self.sample_light(&ray, &hit, world, lights);
+                radiance += throughput * self.light_intensity * direct;
+            }
+            if scatter.pdf.is_some() {
+                let direct = self.sample_delta_lights(&ray, &hit, world);
+                radiance += throughput * self.light_intensity * direct;
+            }
+
+            throughput *= self.light_intensity * scatter.attenuation;
+            bsdf_pdf = scatter.pdf.filter(|&pdf| pdf > 0.0);
+            ray = scatter.ray;
         }
 
-        if let Some(hit) = world.hit(ray, &Interval::new(0.001, std::f32::INFINITY)) {
-            if let Some((cl, scattered)) = hit.mat().scatter(ray, &hit) {
-                let color = cl * self.ray_color(&scattered, depth - 1, world);
-                return self.light_intensity * color;
+        radiance
+    }
+
+    /// Radiance reflected towards `ray` from one direction sampled towards
+    /// `lights` or an importance sampled background, weighted against the
+    /// BSDF strategy.
+    fn sample_light(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        world: &dyn Hittable,
+        lights: &HittableList,
+    ) -> Color {
+        let direction =
+            if lights.is_empty() || (self.background.importance_sampled() && rand() < 0.5) {
+                self.background.random()
+            } else {
+                lights.random(hit.p())
+            };
+        let light_ray = Ray::with_time(*hit.p(), direction, ray.time());
+        let light_pdf = self.light_pdf(lights, light_ray.orig(), light_ray.dir());
+        if !light_pdf.is_finite() || light_pdf <= 0.0 {
+            return Color::ZERO;
+        }
+
+        let wi = light_ray.dir().normalize();
+        let wo = -ray.dir().normalize();
+        let bsdf = hit.mat().bsdf_eval(hit, &wi, &wo);
+        if bsdf == Color::ZERO {
+            return Color::ZERO;
+        }
+
+        let incoming = match world.hit(&light_ray, &Interval::new(0.001, f32::INFINITY)) {
+            Some(light_hit) => light_hit.mat().emitted(&light_hit),
+            None => self.background.color(&light_ray),
+        };
+
+        let bsdf_pdf = hit.mat().pdf(hit, &wi, &wo);
+        incoming * bsdf * power_heuristic(light_pdf, bsdf_pdf) / light_pdf
+    }
+
+    /// Light reflected towards `ray` from every unoccluded delta light.
+    fn sample_delta_lights(&self, ray: &Ray, hit: &HitRecord, world: &dyn Hittable) -> Color {
+        let wo = -ray.dir().normalize();
+        let mut radiance = Color::ZERO;
+
+        for light in &self.delta_lights {
+            let Some(sample) = light.sample(hit.p()) else {
+                continue;
+            };
+
+            let bsdf = hit.mat().bsdf_eval(hit, &sample.direction, &wo);
+            if bsdf == Color::ZERO {
+                continue;
             }
 
-            return Color::new(0.0, 0.0, 0.0);
+            let shadow_ray = Ray::with_time(*hit.p(), sample.direction, ray.time());
+            let unoccluded = Interval::new(0.001, sample.distance * (1.0 - 1e-4));
+            if world.hit(&shadow_ray, &unoccluded).is_none() {
+                radiance += bsdf * sample.irradiance;
+            }
         }
 
-        let unit = ray.dir().normalize();
-        let a = 0.5 * (unit.y + 1.0);
-        (1.0 - a) * Vector3::new(1.0, 1.0, 1.0) + a * self.sky_color
+        radiance
+    }
+
+    /// Density of [`Camera::sample_light`] choosing `direction`, an even
+    /// mixture of `lights` and the background when both are sampled.
+    fn light_pdf(&self, lights: &HittableList, origin: &Point3, direction: &Vector3) -> f32 {
+        let background = self.background.importance_sampled();
+        match (lights.is_empty(), background) {
+            (false, true) => {
+                0.5 * (lights.pdf_value(origin, direction) + self.background.pdf_value(direction))
+            }
+            (false, false) => lights.pdf_value(origin, direction),
+            (true, true) => self.background.pdf_value(direction),
+            (true, false) => 0.0,
+        }
     }
 
     fn sample_square(&self) -> Vector3 {
@@ -148,3 +428,9 @@ impl Camera {
         self.center + (p.x * self.defocus_disk_u) + (p.y * self.defocus_disk_v)
     }
 }
+
+/// Power heuristic (beta = 2) weight of a strategy with density `pdf` against `other`.
+fn power_heuristic(pdf: f32, other: f32) -> f32 {
+    let (a, b) = (pdf * pdf, other * other);
+    if a + b == 0.0 { 0.0 } else { a / (a + b) }
+}
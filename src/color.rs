@@ -1,29 +1,50 @@
+pub mod space;
+
 use crate::math::Vector3;
 
+pub use space::{ColorSpace, xyz_to_srgb};
+
 pub type Color = Vector3;
 
-fn linear_to_gamma(linear_component: f32) -> f32 {
-    if linear_component > 0.0 {
-        linear_component.sqrt()
-    } else {
+/// sRGB OETF, encodes a linear component for display.
+pub fn linear_to_srgb(linear: f32) -> f32 {
+    if linear <= 0.0 {
         0.0
+    } else if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
     }
 }
 
+/// Inverse of [`linear_to_srgb`], used for 8-bit texture inputs.
+pub fn srgb_to_linear(encoded: f32) -> f32 {
+    if encoded <= 0.04045 {
+        encoded / 12.92
+    } else {
+        ((encoded + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Rec. 709 luminance of a linear color.
+pub fn luminance(color: Color) -> f32 {
+    color.dot(Color::new(0.2126, 0.7152, 0.0722))
+}
+
 pub fn vec3_to_color(vec: &Vector3) -> u32 {
-    let r = linear_to_gamma(vec.x);
-    let g = linear_to_gamma(vec.y);
-    let b = linear_to_gamma(vec.z);
+    let r = linear_to_srgb(vec.x);
+    let g = linear_to_srgb(vec.y);
+    let b = linear_to_srgb(vec.z);
 
-    let r = (r * 255.0).clamp(0.0, 255.0) as u8;
-    let g = (g * 255.0).clamp(0.0, 255.0) as u8;
-    let b = (b * 255.0).clamp(0.0, 255.0) as u8;
-    (0xff as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32
+    let r = (r * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+    let g = (g * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+    let b = (b * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+    0xff_u32 << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32
 }
 
 pub fn floats_to_color(r: f64, g: f64, b: f64) -> u32 {
     let r = (r * 255.0).clamp(0.0, 255.0) as u8;
     let g = (g * 255.0).clamp(0.0, 255.0) as u8;
     let b = (b * 255.0).clamp(0.0, 255.0) as u8;
-    (0xff as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32
+    0xff_u32 << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32
 }
@@ -0,0 +1,86 @@
+use serde::Deserialize;
+
+use super::Color;
+
+/// Linear RGB space the renderer does its arithmetic in.
+///
+/// Scene colors and textures are authored in sRGB and converted on load,
+/// outputs are converted back to linear sRGB primaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum ColorSpace {
+    /// Rec.709 primaries, D65 white.
+    #[default]
+    #[serde(rename = "linear_srgb", alias = "srgb")]
+    LinearSrgb,
+    /// Rec.2020 primaries, D65 white.
+    #[serde(rename = "rec2020")]
+    Rec2020,
+    /// ACES AP1 primaries, D60 white (Bradford adapted from D65).
+    #[serde(rename = "acescg")]
+    AcesCg,
+}
+
+type Matrix = [[f32; 3]; 3];
+
+const SRGB_TO_REC2020: Matrix = [
+    [0.627404, 0.329282, 0.0433136],
+    [0.069097, 0.91954, 0.0113612],
+    [0.0163916, 0.0880132, 0.895595],
+];
+
+const REC2020_TO_SRGB: Matrix = [
+    [1.660491, -0.5876411, -0.0728499],
+    [-0.1245505, 1.1328999, -0.0083494],
+    [-0.0181508, -0.1005789, 1.1187297],
+];
+
+const SRGB_TO_ACESCG: Matrix = [
+    [0.6131324, 0.3395381, 0.0473296],
+    [0.0701934, 0.9163539, 0.0134527],
+    [0.0206155, 0.1095697, 0.8698148],
+];
+
+const ACESCG_TO_SRGB: Matrix = [
+    [1.7048586, -0.621716, -0.0831426],
+    [-0.1300768, 1.1407357, -0.0106589],
+    [-0.023964, -0.1289755, 1.1529395],
+];
+
+const XYZ_TO_SRGB: Matrix = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.969266, 1.8760108, 0.041556],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+/// Converts CIE XYZ (D65) to linear sRGB.
+pub fn xyz_to_srgb(xyz: Color) -> Color {
+    mul(&XYZ_TO_SRGB, xyz)
+}
+
+impl ColorSpace {
+    /// Converts a linear sRGB color into this space.
+    pub fn from_srgb(&self, color: Color) -> Color {
+        match self {
+            ColorSpace::LinearSrgb => color,
+            ColorSpace::Rec2020 => mul(&SRGB_TO_REC2020, color),
+            ColorSpace::AcesCg => mul(&SRGB_TO_ACESCG, color),
+        }
+    }
+
+    /// Converts a color in this space to linear sRGB.
+    pub fn to_srgb(&self, color: Color) -> Color {
+        match self {
+            ColorSpace::LinearSrgb => color,
+            ColorSpace::Rec2020 => mul(&REC2020_TO_SRGB, color),
+            ColorSpace::AcesCg => mul(&ACESCG_TO_SRGB, color),
+        }
+    }
+}
+
+fn mul(m: &Matrix, c: Color) -> Color {
+    Color::new(
+        Color::from_array(m[0]).dot(c),
+        Color::from_array(m[1]).dot(c),
+        Color::from_array(m[2]).dot(c),
+    )
+}
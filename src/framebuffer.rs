@@ -0,0 +1,53 @@
+use glam::Vec4;
+
+use crate::Buffer;
+use crate::color::Color;
+use crate::tonemap::DisplayTransform;
+
+/// Linear RGBA radiance image with `f32` channels, values are not clamped.
+pub struct FrameBuffer {
+    pixels: Vec<Vec4>,
+    width: usize,
+    height: usize,
+}
+
+impl FrameBuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            pixels: vec![Vec4::new(0.0, 0.0, 0.0, 1.0); width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn set_color(&mut self, x: usize, y: usize, color: &Color) -> &Self {
+        self.pixels[y * self.width + x] = color.extend(1.0);
+        self
+    }
+
+    pub fn color(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x].truncate().into()
+    }
+
+    pub fn pixels(&self) -> &[Vec4] {
+        &self.pixels
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Converts to the 8-bit display buffer: exposure and tone mapping first,
+    /// then gamma encoding.
+    pub fn to_buffer(&self, bf: &mut Buffer, display: &DisplayTransform) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                bf.set_color(x, y, &display.apply(self.color(x, y)));
+            }
+        }
+    }
+}
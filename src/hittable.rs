@@ -1,20 +1,39 @@
+pub mod bvh;
+pub mod constant_medium;
+pub mod disk;
+pub mod instance;
+pub mod mesh;
+pub mod plane;
+pub mod quad;
 pub mod sphere;
+pub mod triangle;
 
-use std::rc::Rc;
+pub use bvh::BvhNode;
+pub use constant_medium::ConstantMedium;
+pub use disk::Disk;
+pub use instance::{Instance, Keyframe};
+pub use mesh::TriangleMesh;
+pub use plane::Plane;
+pub use quad::{Quad, cuboid};
+pub use triangle::Triangle;
 
-use crate::math::{Point3, Vector3};
+use std::sync::Arc;
+
+use crate::math::{Point3, Vector3, rand};
 
 use crate::{
     materials::Material,
-    math::{Interval, Ray},
+    math::{Aabb, Interval, Ray},
 };
 
 pub struct HitRecord {
     p: Point3,
     normal: Vector3,
     t: f32,
+    u: f32,
+    v: f32,
     front_face: bool,
-    mat: Rc<dyn Material>,
+    mat: Arc<dyn Material>,
 }
 
 impl HitRecord {
@@ -22,13 +41,17 @@ impl HitRecord {
         p: Point3,
         normal: Vector3,
         t: f32,
+        u: f32,
+        v: f32,
         front_face: bool,
-        mat: Rc<dyn Material>,
+        mat: Arc<dyn Material>,
     ) -> Self {
         Self {
             p,
             normal,
             t,
+            u,
+            v,
             front_face,
             mat,
         }
@@ -46,39 +69,85 @@ impl HitRecord {
         self.t
     }
 
+    /// Surface texture coordinates of the hit point.
+    pub fn u(&self) -> f32 {
+        self.u
+    }
+
+    pub fn v(&self) -> f32 {
+        self.v
+    }
+
     pub fn front_face(&self) -> bool {
         self.front_face
     }
 
-    pub fn mat(&self) -> &Rc<dyn Material> {
+    pub fn mat(&self) -> &Arc<dyn Material> {
         &self.mat
     }
 }
 
-pub trait Hittable {
+pub trait Hittable: Send + Sync {
     fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord>;
+
+    fn bounding_box(&self) -> Aabb;
+
+    /// Solid angle density of [`Hittable::random`] choosing `direction` from
+    /// `origin`, zero for objects that can't be sampled as lights.
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vector3) -> f32 {
+        0.0
+    }
+
+    /// Direction from `origin` towards a random point on the object.
+    fn random(&self, _origin: &Point3) -> Vector3 {
+        Vector3::X
+    }
 }
 
 pub struct HittableList {
     objects: Vec<Box<dyn Hittable>>,
+    bbox: Aabb,
+}
+
+impl Default for HittableList {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HittableList {
     pub fn new() -> Self {
         Self {
             objects: Vec::new(),
+            bbox: Aabb::EMPTY,
         }
     }
 
     pub fn add(&mut self, object: Box<dyn Hittable>) {
+        self.bbox = Aabb::union(&self.bbox, &object.bounding_box());
         self.objects.push(object);
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.bbox = Aabb::EMPTY;
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
     }
 
-    pub fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+    pub fn into_objects(self) -> Vec<Box<dyn Hittable>> {
+        self.objects
+    }
+}
+
+impl Hittable for HittableList {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
         let mut closest_so_far = ray_t.max();
         let mut hit_record = None;
 
@@ -93,4 +162,27 @@ impl HittableList {
 
         hit_record
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Mixture density of picking an object uniformly and then sampling it.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f32 = self
+            .objects
+            .iter()
+            .map(|object| object.pdf_value(origin, direction))
+            .sum();
+        sum / self.objects.len() as f32
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let index = ((rand() * self.objects.len() as f32) as usize).min(self.objects.len() - 1);
+        self.objects[index].random(origin)
+    }
 }
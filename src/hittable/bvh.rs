@@ -0,0 +1,159 @@
+use crate::math::{Aabb, Interval, Point3, Ray};
+
+use super::{HitRecord, Hittable, HittableList};
+
+/// Bounding volume hierarchy built with the surface area heuristic.
+///
+/// Leaves are the original objects, so a subtree with a single object is the
+/// object itself rather than another node. Unbounded objects like planes are
+/// kept in a list next to the tree, as they can't be split spatially.
+pub struct BvhNode {
+    left: Box<dyn Hittable>,
+    right: Option<Box<dyn Hittable>>,
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = list
+            .into_objects()
+            .into_iter()
+            .partition(|object| object.bounding_box().is_bounded());
+
+        let tree = Self::build(bounded);
+        if unbounded.is_empty() {
+            return tree;
+        }
+
+        let mut planes = HittableList::new();
+        for object in unbounded {
+            planes.add(object);
+        }
+        Self {
+            bbox: Aabb::union(&tree.bbox, &planes.bounding_box()),
+            left: Box::new(tree),
+            right: Some(Box::new(planes)),
+        }
+    }
+
+    fn build(mut objects: Vec<Box<dyn Hittable>>) -> Self {
+        match objects.len() {
+            0 => Self {
+                left: Box::new(HittableList::new()),
+                right: None,
+                bbox: Aabb::EMPTY,
+            },
+            1 => {
+                let left = objects.pop().unwrap();
+                Self {
+                    bbox: left.bounding_box(),
+                    left,
+                    right: None,
+                }
+            }
+            _ => {
+                let boxes: Vec<Aabb> = objects.iter().map(|o| o.bounding_box()).collect();
+                let centroids: Vec<Point3> = boxes.iter().map(Aabb::centroid).collect();
+                let (axis, split) = Self::sah_split(&boxes, &centroids);
+
+                // Stable, so ties keep their index order as in `sah_split`.
+                let mut keyed: Vec<_> = centroids.iter().map(|c| c[axis]).zip(objects).collect();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let mut objects: Vec<_> = keyed.into_iter().map(|(_, o)| o).collect();
+
+                let right_objects = objects.split_off(split);
+                let left = Self::child(objects);
+                let right = Self::child(right_objects);
+
+                Self {
+                    bbox: Aabb::union(&left.bounding_box(), &right.bounding_box()),
+                    left,
+                    right: Some(right),
+                }
+            }
+        }
+    }
+
+    fn child(mut objects: Vec<Box<dyn Hittable>>) -> Box<dyn Hittable> {
+        if objects.len() == 1 {
+            objects.pop().unwrap()
+        } else {
+            Box::new(Self::build(objects))
+        }
+    }
+
+    /// Sweeps every axis over the objects sorted by centroid and returns the
+    /// axis and split index with the lowest SAH cost.
+    fn sah_split(boxes: &[Aabb], centroids: &[Point3]) -> (usize, usize) {
+        let n = boxes.len();
+
+        let (c_min, c_max) = centroids
+            .iter()
+            .fold((centroids[0], centroids[0]), |(lo, hi), c| {
+                (lo.min(*c), hi.max(*c))
+            });
+        if (c_max - c_min).max_element() <= 0.0 {
+            return (0, n / 2);
+        }
+
+        let mut best = (0, n / 2);
+        let mut best_cost = f32::INFINITY;
+        let mut order: Vec<usize> = (0..n).collect();
+        let mut right_area = vec![0.0; n];
+
+        for axis in 0..3 {
+            if c_max[axis] - c_min[axis] <= 0.0 {
+                continue;
+            }
+            // `order` still holds the previous axis's sort, so ties are broken
+            // by index to match the order `build` splits the objects in.
+            order.sort_by(|&a, &b| {
+                centroids[a][axis]
+                    .total_cmp(&centroids[b][axis])
+                    .then(a.cmp(&b))
+            });
+
+            let mut acc = Aabb::EMPTY;
+            for i in (1..n).rev() {
+                acc = Aabb::union(&acc, &boxes[order[i]]);
+                right_area[i] = acc.surface_area();
+            }
+
+            let mut acc = Aabb::EMPTY;
+            for i in 1..n {
+                acc = Aabb::union(&acc, &boxes[order[i - 1]]);
+                let cost = i as f32 * acc.surface_area() + (n - i) as f32 * right_area[i];
+                if cost < best_cost {
+                    best_cost = cost;
+                    best = (axis, i);
+                }
+            }
+        }
+
+        best
+    }
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        if !self.bbox.hit(ray, ray_t) {
+            return None;
+        }
+
+        let left_hit = self.left.hit(ray, ray_t);
+        let Some(right) = &self.right else {
+            return left_hit;
+        };
+
+        let mut interval = *ray_t;
+        if let Some(hit) = &left_hit {
+            interval.set_max(hit.t());
+        }
+
+        right.hit(ray, &interval).or(left_hit)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::materials::Material;
+use crate::math::{Aabb, Interval, Ray, UNIVERSE, Vector3, rand};
+
+use super::{HitRecord, Hittable};
+
+/// Homogeneous participating medium filling a closed `boundary`.
+///
+/// Rays travel an exponentially distributed distance before scattering off
+/// `phase_function`, usually an [`crate::materials::Isotropic`].
+pub struct ConstantMedium {
+    boundary: Box<dyn Hittable>,
+    neg_inv_density: f32,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(
+        boundary: Box<dyn Hittable>,
+        density: f32,
+        phase_function: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let enter = self.boundary.hit(ray, &UNIVERSE)?;
+        let exit = self
+            .boundary
+            .hit(ray, &Interval::new(enter.t() + 0.0001, f32::INFINITY))?;
+
+        let t_enter = enter.t().max(ray_t.min()).max(0.0);
+        let t_exit = exit.t().min(ray_t.max());
+        if t_enter >= t_exit {
+            return None;
+        }
+
+        let ray_length = ray.dir().length();
+        let distance_inside = (t_exit - t_enter) * ray_length;
+        let hit_distance = self.neg_inv_density * (1.0 - rand()).ln();
+        if hit_distance > distance_inside {
+            return None;
+        }
+
+        let t = t_enter + hit_distance / ray_length;
+        Some(HitRecord::new(
+            ray.at(t),
+            // Arbitrary, phase functions don't use the normal.
+            Vector3::X,
+            t,
+            0.0,
+            0.0,
+            true,
+            Arc::clone(&self.phase_function),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,90 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::materials::Material;
+use crate::math::{Aabb, Interval, Onb, Point3, Ray, Vector3, rand};
+
+use super::plane::{area_pdf, intersect_plane, surface_hit};
+use super::{HitRecord, Hittable};
+
+/// Flat disk facing `normal`.
+///
+/// `u` is the angle around the center as a fraction of a turn and `v` the
+/// distance from the center relative to the radius.
+#[derive(Clone)]
+pub struct Disk {
+    center: Point3,
+    radius: f32,
+    frame: Onb,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vector3, radius: f32, mat: Arc<dyn Material>) -> Self {
+        let frame = Onb::new(&normal);
+        // Extent of the disk along each axis is `radius * sin` of the angle
+        // between the axis and the normal.
+        let n = *frame.w();
+        let sin = |c: f32| (1.0 - c * c).max(0.0).sqrt();
+        let extent = radius * Vector3::new(sin(n.x), sin(n.y), sin(n.z));
+
+        Self {
+            center,
+            radius,
+            frame,
+            mat,
+            bbox: Aabb::from_points(center - extent, center + extent),
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let normal = *self.frame.w();
+        let t = intersect_plane(ray, ray_t, &self.center, &normal)?;
+
+        let p = ray.at(t);
+        let local = self.frame.to_local(&(p - self.center));
+        let r = local.x.hypot(local.y);
+        if r > self.radius {
+            return None;
+        }
+
+        let phi = local.y.atan2(local.x);
+        let u = phi.rem_euclid(2.0 * PI) / (2.0 * PI);
+        Some(surface_hit(
+            ray,
+            p,
+            normal,
+            t,
+            (u, r / self.radius),
+            &self.mat,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Uniform over the disk's area, converted to solid angle.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let ray = Ray::new(*origin, *direction);
+        let Some(hit) = self.hit(&ray, &Interval::new(0.001, f32::INFINITY)) else {
+            return 0.0;
+        };
+
+        let area = PI * self.radius * self.radius;
+        area_pdf(hit.t(), direction, self.frame.w(), area)
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let r = self.radius * rand().sqrt();
+        let phi = 2.0 * PI * rand();
+        let offset = self
+            .frame
+            .local(&Vector3::new(r * phi.cos(), r * phi.sin(), 0.0));
+
+        self.center + offset - origin
+    }
+}
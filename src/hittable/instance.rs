@@ -0,0 +1,258 @@
+use std::sync::Arc;
+
+use glam::{Affine3A, Mat3A, Quat, Vec3};
+
+use crate::math::{Aabb, Interval, Point3, Ray, Vector3};
+
+use super::{HitRecord, Hittable};
+
+/// Sub-steps between keyframes used to bound the swept motion.
+const MOTION_BOUND_STEPS: usize = 16;
+
+/// Places a shared `object` in the scene through an affine transform,
+/// optionally animated over time.
+///
+/// Rays are moved into object space without renormalizing their direction,
+/// so hit distances are the same in both spaces.
+#[derive(Clone)]
+pub struct Instance {
+    object: Arc<dyn Hittable>,
+    motion: Motion,
+    bbox: Aabb,
+}
+
+/// Object-to-world transform at `time`, interpolated between keyframes.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub time: f32,
+    pub scale: Vec3,
+    pub rotation: Quat,
+    pub translation: Vec3,
+}
+
+impl Keyframe {
+    /// Splits `transform` into scale, rotation and translation, which must be
+    /// possible for the result to interpolate as expected.
+    pub fn new(time: f32, transform: Affine3A) -> Self {
+        let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+        Self {
+            time,
+            scale,
+            rotation,
+            translation,
+        }
+    }
+
+    /// Pose at `time`, which is clamped to the two keyframes.
+    fn interpolate(&self, other: &Keyframe, time: f32) -> Keyframe {
+        let s = ((time - self.time) / (other.time - self.time)).clamp(0.0, 1.0);
+        Keyframe {
+            time,
+            scale: self.scale.lerp(other.scale, s),
+            rotation: self.rotation.slerp(other.rotation, s),
+            translation: self.translation.lerp(other.translation, s),
+        }
+    }
+
+    fn transform(&self) -> Affine3A {
+        Affine3A::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+#[derive(Clone)]
+enum Motion {
+    Static(Transform),
+    /// Sorted by time, with at least two entries.
+    Keyframed(Vec<Keyframe>),
+}
+
+#[derive(Clone, Copy)]
+struct Transform {
+    object_to_world: Affine3A,
+    world_to_object: Affine3A,
+    normal_to_world: Mat3A,
+}
+
+impl Transform {
+    fn new(object_to_world: Affine3A) -> Self {
+        let world_to_object = object_to_world.inverse();
+        Self {
+            object_to_world,
+            world_to_object,
+            normal_to_world: world_to_object.matrix3.transpose(),
+        }
+    }
+}
+
+impl Instance {
+    pub fn new(object: Arc<dyn Hittable>, transform: Affine3A) -> Self {
+        let bbox = transform_box(&object.bounding_box(), &transform);
+        Self {
+            object,
+            motion: Motion::Static(Transform::new(transform)),
+            bbox,
+        }
+    }
+
+    /// Instance moving through `keyframes`, holding the first and last pose
+    /// outside their time range.
+    ///
+    /// Light sampling assumes static geometry, moving instances shouldn't be
+    /// used as lights.
+    pub fn keyframed(object: Arc<dyn Hittable>, mut keyframes: Vec<Keyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        if keyframes.len() < 2 {
+            let transform = keyframes
+                .first()
+                .map_or(Affine3A::IDENTITY, Keyframe::transform);
+            return Self::new(object, transform);
+        }
+
+        // Rotations make the swept box bulge between keyframes, bound it
+        // over short steps of each segment.
+        let object_box = object.bounding_box();
+        let mut bbox = Aabb::EMPTY;
+        for pair in keyframes.windows(2) {
+            let mut previous = pair[0];
+            for step in 1..=MOTION_BOUND_STEPS {
+                let s = step as f32 / MOTION_BOUND_STEPS as f32;
+                let time = pair[0].time + s * (pair[1].time - pair[0].time);
+                let pose = pair[0].interpolate(&pair[1], time);
+                bbox = Aabb::union(&bbox, &swept_box(&object_box, &previous, &pose));
+                previous = pose;
+            }
+        }
+
+        Self {
+            object,
+            motion: Motion::Keyframed(keyframes),
+            bbox,
+        }
+    }
+
+    fn transform_at(&self, time: f32) -> Transform {
+        let keyframes = match &self.motion {
+            Motion::Static(transform) => return *transform,
+            Motion::Keyframed(keyframes) => keyframes,
+        };
+
+        let next = keyframes
+            .partition_point(|k| k.time <= time)
+            .clamp(1, keyframes.len() - 1);
+        Transform::new(
+            keyframes[next - 1]
+                .interpolate(&keyframes[next], time)
+                .transform(),
+        )
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let transform = self.transform_at(ray.time());
+        let object_ray = Ray::with_time(
+            transform.world_to_object.transform_point3a(*ray.orig()),
+            transform.world_to_object.transform_vector3a(*ray.dir()),
+            ray.time(),
+        );
+        let hit = self.object.hit(&object_ray, ray_t)?;
+
+        Some(HitRecord::new(
+            transform.object_to_world.transform_point3a(*hit.p()),
+            (transform.normal_to_world * *hit.normal()).normalize(),
+            hit.t(),
+            hit.u(),
+            hit.v(),
+            hit.front_face(),
+            Arc::clone(hit.mat()),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// The object's density, scaled by how the transform stretches solid angles.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let transform = self.transform_at(0.0);
+        let to_object = transform.world_to_object.matrix3;
+        let direction = to_object * direction.normalize();
+        let length = direction.length();
+
+        let pdf = self.object.pdf_value(
+            &transform.world_to_object.transform_point3a(*origin),
+            &(direction / length),
+        );
+        pdf * to_object.determinant().abs() / (length * length * length)
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let transform = self.transform_at(0.0);
+        let direction = self
+            .object
+            .random(&transform.world_to_object.transform_point3a(*origin));
+        transform.object_to_world.transform_vector3a(direction)
+    }
+}
+
+/// Box enclosing the eight transformed corners of `bbox`.
+fn transform_box(bbox: &Aabb, transform: &Affine3A) -> Aabb {
+    if bbox.is_empty() {
+        return *bbox;
+    }
+    if !bbox.is_bounded() {
+        return Aabb::UNIVERSE;
+    }
+
+    let (mut lo, mut hi) = (Point3::INFINITY, Point3::NEG_INFINITY);
+    for corner in corners(bbox) {
+        let p = transform.transform_point3a(corner);
+        lo = lo.min(p);
+        hi = hi.max(p);
+    }
+
+    Aabb::from_points(lo, hi)
+}
+
+/// Box enclosing `bbox` while it moves from pose `a` to pose `b`.
+///
+/// Every point of the object turns by the angle 2α between the two rotations
+/// while its scaled position `v` changes linearly, so it strays from the
+/// straight line between its end positions by at most
+/// `√2·|v|·(1 − cos α) + ½·sin α·|Δv|`. The box around both poses is grown by
+/// that bound, taken over the corners as it is convex in the point.
+fn swept_box(bbox: &Aabb, a: &Keyframe, b: &Keyframe) -> Aabb {
+    let ends = Aabb::union(
+        &transform_box(bbox, &a.transform()),
+        &transform_box(bbox, &b.transform()),
+    );
+    if bbox.is_empty() || !bbox.is_bounded() {
+        return ends;
+    }
+
+    let (mut radius, mut stretch) = (0.0f32, 0.0f32);
+    for corner in corners(bbox) {
+        let (va, vb) = (a.scale * Vec3::from(corner), b.scale * Vec3::from(corner));
+        radius = radius.max(va.length()).max(vb.length());
+        stretch = stretch.max((vb - va).length());
+    }
+
+    let half_angle = a.rotation.angle_between(b.rotation) / 2.0;
+    let slack = std::f32::consts::SQRT_2 * radius * (1.0 - half_angle.cos())
+        + 0.5 * half_angle.sin() * stretch;
+    Aabb::from_points(
+        ends.min() - Point3::splat(slack),
+        ends.max() + Point3::splat(slack),
+    )
+}
+
+fn corners(bbox: &Aabb) -> [Point3; 8] {
+    let (min, max) = (bbox.min(), bbox.max());
+    std::array::from_fn(|i| {
+        Point3::new(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        )
+    })
+}
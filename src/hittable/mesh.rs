@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use glam::Vec2;
+
+use crate::materials::Material;
+use crate::math::{Aabb, Interval, Point3, Ray, Vector3};
+
+use super::triangle::{intersect, shade, triangle_bbox};
+use super::{BvhNode, HitRecord, Hittable, HittableList};
+
+/// Vertex attribute buffers shared by every triangle of a mesh.
+#[derive(Default)]
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vector3>,
+    pub uvs: Vec<Vec2>,
+}
+
+/// One face corner, indexing into the [`MeshData`] buffers.
+#[derive(Debug, Clone, Copy)]
+pub struct MeshVertex {
+    pub position: usize,
+    pub normal: Option<usize>,
+    pub uv: Option<usize>,
+}
+
+pub struct MeshFace {
+    pub vertices: [MeshVertex; 3],
+    /// Index into the material list passed to [`TriangleMesh::new`].
+    pub material: usize,
+}
+
+/// Indexed triangle mesh with its own BVH over the faces.
+pub struct TriangleMesh {
+    bvh: BvhNode,
+    triangles: usize,
+}
+
+impl TriangleMesh {
+    pub fn new(data: MeshData, faces: Vec<MeshFace>, materials: Vec<Arc<dyn Material>>) -> Self {
+        let data = Arc::new(data);
+        let triangles = faces.len();
+
+        let mut list = HittableList::new();
+        for face in faces {
+            list.add(Box::new(MeshTriangle::new(
+                Arc::clone(&data),
+                face.vertices,
+                Arc::clone(&materials[face.material]),
+            )));
+        }
+
+        Self {
+            bvh: BvhNode::new(list),
+            triangles,
+        }
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangles
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        self.bvh.hit(ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+}
+
+struct MeshTriangle {
+    data: Arc<MeshData>,
+    vertices: [MeshVertex; 3],
+    mat: Arc<dyn Material>,
+}
+
+impl MeshTriangle {
+    fn new(data: Arc<MeshData>, vertices: [MeshVertex; 3], mat: Arc<dyn Material>) -> Self {
+        Self {
+            data,
+            vertices,
+            mat,
+        }
+    }
+
+    fn positions(&self) -> [Point3; 3] {
+        self.vertices.map(|v| self.data.positions[v.position])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let positions = self.positions();
+        let (t, b1, b2) = intersect(ray, ray_t, &positions)?;
+
+        let normals = match self.vertices.map(|v| v.normal) {
+            [Some(a), Some(b), Some(c)] => Some([a, b, c].map(|i| self.data.normals[i])),
+            _ => None,
+        };
+        let uvs = match self.vertices.map(|v| v.uv) {
+            [Some(a), Some(b), Some(c)] => Some([a, b, c].map(|i| self.data.uvs[i])),
+            _ => None,
+        };
+
+        Some(shade(
+            ray,
+            t,
+            (b1, b2),
+            &positions,
+            normals.as_ref(),
+            uvs.as_ref(),
+            &self.mat,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        triangle_bbox(&self.positions())
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::materials::Material;
+use crate::math::{Aabb, Interval, Onb, Point3, Ray, Vector3};
+
+use super::{HitRecord, Hittable};
+
+/// Infinite plane through `point`.
+///
+/// (u, v) are the hit's coordinates along two tangent axes, in world units.
+/// The bounding box is unbounded, [`super::BvhNode`] keeps such objects out of
+/// the tree.
+pub struct Plane {
+    point: Point3,
+    frame: Onb,
+    mat: Arc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vector3, mat: Arc<dyn Material>) -> Self {
+        Self {
+            point,
+            frame: Onb::new(&normal),
+            mat,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let normal = *self.frame.w();
+        let t = intersect_plane(ray, ray_t, &self.point, &normal)?;
+
+        let p = ray.at(t);
+        let offset = p - self.point;
+        Some(surface_hit(
+            ray,
+            p,
+            normal,
+            t,
+            (offset.dot(*self.frame.u()), offset.dot(*self.frame.v())),
+            &self.mat,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::UNIVERSE
+    }
+}
+
+/// Distance along `ray` to the plane through `point`, `None` for parallel rays
+/// and hits outside `ray_t`.
+pub(crate) fn intersect_plane(
+    ray: &Ray,
+    ray_t: &Interval,
+    point: &Point3,
+    normal: &Vector3,
+) -> Option<f32> {
+    let denom = normal.dot(*ray.dir());
+    if denom.abs() < 1e-8 {
+        return None;
+    }
+
+    let t = normal.dot(*point - ray.orig()) / denom;
+    ray_t.surrounds(t).then_some(t)
+}
+
+/// Hit record of a flat surface, with `normal` flipped to face the ray.
+pub(crate) fn surface_hit(
+    ray: &Ray,
+    p: Point3,
+    normal: Vector3,
+    t: f32,
+    (u, v): (f32, f32),
+    mat: &Arc<dyn Material>,
+) -> HitRecord {
+    let front_face = ray.dir().dot(normal) < 0.0;
+    let normal = if front_face { normal } else { -normal };
+
+    HitRecord::new(p, normal, t, u, v, front_face, Arc::clone(mat))
+}
+
+/// Solid angle density of sampling a flat light of `area` uniformly, for a
+/// `direction` hitting it at `t`.
+pub(crate) fn area_pdf(t: f32, direction: &Vector3, normal: &Vector3, area: f32) -> f32 {
+    let distance_squared = t * t * direction.length_squared();
+    let cosine = (direction.dot(*normal) / direction.length()).abs();
+    if cosine < 1e-6 {
+        return 0.0;
+    }
+
+    distance_squared / (cosine * area)
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use crate::materials::Material;
+use crate::math::{Aabb, Interval, Point3, Ray, Vector3, rand};
+
+use super::plane::{area_pdf, intersect_plane, surface_hit};
+use super::{HitRecord, Hittable, HittableList};
+
+/// Parallelogram with corner `q` and edges `u` and `v`.
+///
+/// (u, v) run from 0 to 1 along the two edges.
+#[derive(Clone)]
+pub struct Quad {
+    q: Point3,
+    u: Vector3,
+    v: Vector3,
+    /// `n / (n · n)` with `n = u × v`, maps planar offsets to edge coordinates.
+    w: Vector3,
+    normal: Vector3,
+    area: f32,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vector3, v: Vector3, mat: Arc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        let bbox = Aabb::union(
+            &Aabb::from_points(q, q + u + v),
+            &Aabb::from_points(q + u, q + v),
+        );
+
+        Self {
+            q,
+            u,
+            v,
+            w: n / n.length_squared(),
+            normal: n.normalize(),
+            area: n.length(),
+            mat,
+            bbox,
+        }
+    }
+
+    /// Edge coordinates of `p`'s projection onto the quad, `None` outside it.
+    fn coordinates(&self, p: &Point3) -> Option<(f32, f32)> {
+        let offset = p - self.q;
+        let alpha = self.w.dot(offset.cross(self.v));
+        let beta = self.w.dot(self.u.cross(offset));
+
+        let unit = 0.0..=1.0;
+        (unit.contains(&alpha) && unit.contains(&beta)).then_some((alpha, beta))
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let t = intersect_plane(ray, ray_t, &self.q, &self.normal)?;
+        let p = ray.at(t);
+        let uv = self.coordinates(&p)?;
+
+        Some(surface_hit(ray, p, self.normal, t, uv, &self.mat))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Uniform over the quad's area, converted to solid angle.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let ray = Ray::new(*origin, *direction);
+        let Some(hit) = self.hit(&ray, &Interval::new(0.001, f32::INFINITY)) else {
+            return 0.0;
+        };
+
+        area_pdf(hit.t(), direction, &self.normal, self.area)
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        self.q + rand() * self.u + rand() * self.v - origin
+    }
+}
+
+/// Axis-aligned box with opposite corners `a` and `b`, as six outward-facing quads.
+pub fn cuboid(a: Point3, b: Point3, mat: Arc<dyn Material>) -> HittableList {
+    let min = a.min(b);
+    let max = a.max(b);
+    let dx = Vector3::new(max.x - min.x, 0.0, 0.0);
+    let dy = Vector3::new(0.0, max.y - min.y, 0.0);
+    let dz = Vector3::new(0.0, 0.0, max.z - min.z);
+
+    let mut sides = HittableList::new();
+    let faces = [
+        (Point3::new(min.x, min.y, max.z), dx, dy),  // front
+        (Point3::new(max.x, min.y, max.z), -dz, dy), // right
+        (Point3::new(max.x, min.y, min.z), -dx, dy), // back
+        (Point3::new(min.x, min.y, min.z), dz, dy),  // left
+        (Point3::new(min.x, max.y, max.z), dx, -dz), // top
+        (Point3::new(min.x, min.y, min.z), dx, dz),  // bottom
+    ];
+    for (q, u, v) in faces {
+        sides.add(Box::new(Quad::new(q, u, v, Arc::clone(&mat))));
+    }
+
+    sides
+}
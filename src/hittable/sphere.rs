@@ -1,29 +1,89 @@
-use std::rc::Rc;
+use std::f32::consts::PI;
+use std::sync::Arc;
 
-use crate::math::Point3;
+use crate::math::{Aabb, Onb, Point3, Ray, Vector3, rand};
 
 use super::{HitRecord, Hittable};
 use crate::{materials::Material, math::Interval};
 
+#[derive(Clone)]
 pub struct Sphere {
     center: Point3,
+    /// Distance moved per unit of time, zero for static spheres.
+    velocity: Vector3,
     radius: f32,
-    mat: Rc<dyn Material>,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
 }
 
 impl Sphere {
-    pub fn new<M: Material + 'static>(center: Point3, radius: f32, mat: Rc<M>) -> Self {
+    pub fn new(center: Point3, radius: f32, mat: Arc<dyn Material>) -> Self {
+        let rvec = Vector3::splat(radius);
+
         Self {
             center,
+            velocity: Vector3::ZERO,
+            radius,
+            mat,
+            bbox: Aabb::from_points(center - rvec, center + rvec),
+        }
+    }
+
+    /// Sphere moving linearly from `center0` at time 0 to `center1` at time 1.
+    ///
+    /// Light sampling assumes static geometry, moving spheres shouldn't be
+    /// used as lights.
+    pub fn moving(center0: Point3, center1: Point3, radius: f32, mat: Arc<dyn Material>) -> Self {
+        let rvec = Vector3::splat(radius);
+        let bbox = Aabb::union(
+            &Aabb::from_points(center0 - rvec, center0 + rvec),
+            &Aabb::from_points(center1 - rvec, center1 + rvec),
+        );
+
+        Self {
+            center: center0,
+            velocity: center1 - center0,
             radius,
             mat,
+            bbox,
+        }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.velocity != Vector3::ZERO
+    }
+
+    fn center_at(&self, time: f32) -> Point3 {
+        self.center + time * self.velocity
+    }
+
+    /// `1 - cos` of the half-angle of the cone the sphere subtends from
+    /// `origin`, `None` from inside the sphere.
+    fn one_minus_cos_theta_max(&self, origin: &Point3) -> Option<f32> {
+        let distance_squared = (self.center - origin).length_squared();
+        let x = self.radius * self.radius / distance_squared;
+        if x >= 1.0 {
+            return None;
         }
+
+        // Rearranged from `1 - sqrt(1 - x)` to keep precision for distant spheres.
+        Some(x / (1.0 + (1.0 - x).sqrt()))
+    }
+
+    /// Maps a point on the unit sphere to (u, v), with `v` running from the
+    /// bottom pole to the top and `u` around the Y axis starting at -X.
+    fn uv(p: &Point3) -> (f32, f32) {
+        let theta = (-p.y).acos();
+        let phi = (-p.z).atan2(p.x) + PI;
+
+        (phi / (2.0 * PI), theta / PI)
     }
 }
 
 impl Hittable for Sphere {
-    fn hit(&self, ray: &crate::math::Ray, ray_t: &Interval) -> Option<HitRecord> {
-        let oc = self.center - ray.orig();
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let center = self.center_at(ray.time());
+        let oc = center - ray.orig();
 
         let a = ray.dir().length_squared();
         let h = ray.dir().dot(oc);
@@ -44,11 +104,59 @@ impl Hittable for Sphere {
         }
 
         let hit_point = ray.at(root);
-        let normal = (hit_point - self.center).normalize();
+        let normal = (hit_point - center).normalize();
+        let (u, v) = Sphere::uv(&normal);
         let front_face = ray.dir().dot(normal) < 0.0;
         let normal = if front_face { normal } else { -normal };
-        let hit_record = HitRecord::new(hit_point, normal, root, front_face, Rc::clone(&self.mat));
+        let hit_record = HitRecord::new(
+            hit_point,
+            normal,
+            root,
+            u,
+            v,
+            front_face,
+            Arc::clone(&self.mat),
+        );
 
         Some(hit_record)
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Uniform over the cone of directions subtended by the sphere.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let ray = Ray::new(*origin, *direction);
+        if self
+            .hit(&ray, &Interval::new(0.001, f32::INFINITY))
+            .is_none()
+        {
+            return 0.0;
+        }
+
+        let Some(one_minus_cos) = self.one_minus_cos_theta_max(origin) else {
+            return 0.0;
+        };
+        1.0 / (2.0 * PI * one_minus_cos)
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let direction = self.center - origin;
+        let Some(one_minus_cos) = self.one_minus_cos_theta_max(origin) else {
+            return direction;
+        };
+
+        let r1 = rand();
+        let r2 = rand();
+        let z = 1.0 - r2 * one_minus_cos;
+        let phi = 2.0 * PI * r1;
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+
+        Onb::new(&direction).local(&Vector3::new(
+            phi.cos() * sin_theta,
+            phi.sin() * sin_theta,
+            z,
+        ))
+    }
 }
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use glam::Vec2;
+
+use crate::math::{Aabb, Interval, Point3, Ray, Vector3, rand};
+
+use super::{HitRecord, Hittable};
+use crate::materials::Material;
+
+/// Single triangle with optional per-vertex shading normals and UVs.
+///
+/// Without UVs the barycentric coordinates of the hit are used as (u, v).
+#[derive(Clone)]
+pub struct Triangle {
+    vertices: [Point3; 3],
+    normals: Option<[Vector3; 3]>,
+    uvs: Option<[Vec2; 3]>,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Triangle {
+    pub fn new(vertices: [Point3; 3], mat: Arc<dyn Material>) -> Self {
+        Self {
+            vertices,
+            normals: None,
+            uvs: None,
+            mat,
+            bbox: triangle_bbox(&vertices),
+        }
+    }
+
+    pub fn with_normals(mut self, normals: [Vector3; 3]) -> Self {
+        self.normals = Some(normals.map(|n| n.normalize()));
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: [Vec2; 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let (t, b1, b2) = intersect(ray, ray_t, &self.vertices)?;
+
+        Some(shade(
+            ray,
+            t,
+            (b1, b2),
+            &self.vertices,
+            self.normals.as_ref(),
+            self.uvs.as_ref(),
+            &self.mat,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Uniform over the triangle's area, converted to solid angle.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let ray = Ray::new(*origin, *direction);
+        let Some((t, _, _)) = intersect(&ray, &Interval::new(0.001, f32::INFINITY), &self.vertices)
+        else {
+            return 0.0;
+        };
+
+        let cross =
+            (self.vertices[1] - self.vertices[0]).cross(self.vertices[2] - self.vertices[0]);
+        let area = 0.5 * cross.length();
+        let distance_squared = t * t * direction.length_squared();
+        let cosine = (direction.dot(cross) / (direction.length() * cross.length())).abs();
+        if cosine < 1e-6 {
+            return 0.0;
+        }
+
+        distance_squared / (cosine * area)
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let sqrt_r1 = rand().sqrt();
+        let r2 = rand();
+        let (b1, b2) = (sqrt_r1 * (1.0 - r2), sqrt_r1 * r2);
+
+        let [a, b, c] = self.vertices;
+        a + b1 * (b - a) + b2 * (c - a) - origin
+    }
+}
+
+pub(crate) fn triangle_bbox(vertices: &[Point3; 3]) -> Aabb {
+    Aabb::union(
+        &Aabb::from_points(vertices[0], vertices[1]),
+        &Aabb::from_points(vertices[1], vertices[2]),
+    )
+}
+
+/// Möller–Trumbore intersection, returns `t` and the barycentric weights of
+/// the second and third vertices.
+pub(crate) fn intersect(
+    ray: &Ray,
+    ray_t: &Interval,
+    vertices: &[Point3; 3],
+) -> Option<(f32, f32, f32)> {
+    let edge1 = vertices[1] - vertices[0];
+    let edge2 = vertices[2] - vertices[0];
+
+    let pvec = ray.dir().cross(edge2);
+    let det = edge1.dot(pvec);
+    if det.abs() < 1e-8 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let tvec = *ray.orig() - vertices[0];
+    let b1 = tvec.dot(pvec) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let qvec = tvec.cross(edge1);
+    let b2 = ray.dir().dot(qvec) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(qvec) * inv_det;
+    if !ray_t.surrounds(t) {
+        return None;
+    }
+
+    Some((t, b1, b2))
+}
+
+/// Builds the hit record, interpolating shading normals and UVs when present.
+pub(crate) fn shade(
+    ray: &Ray,
+    t: f32,
+    (b1, b2): (f32, f32),
+    vertices: &[Point3; 3],
+    normals: Option<&[Vector3; 3]>,
+    uvs: Option<&[Vec2; 3]>,
+    mat: &Arc<dyn Material>,
+) -> HitRecord {
+    let b0 = 1.0 - b1 - b2;
+
+    let geometric = (vertices[1] - vertices[0])
+        .cross(vertices[2] - vertices[0])
+        .normalize();
+    let front_face = ray.dir().dot(geometric) < 0.0;
+
+    let normal = match normals {
+        Some(n) => (b0 * n[0] + b1 * n[1] + b2 * n[2]).normalize(),
+        None => geometric,
+    };
+    let normal = if (normal.dot(geometric) >= 0.0) == front_face {
+        normal
+    } else {
+        -normal
+    };
+
+    let uv = match uvs {
+        Some(uv) => b0 * uv[0] + b1 * uv[1] + b2 * uv[2],
+        None => Vec2::new(b1, b2),
+    };
+
+    HitRecord::new(
+        ray.at(t),
+        normal,
+        t,
+        uv.x,
+        uv.y,
+        front_face,
+        Arc::clone(mat),
+    )
+}
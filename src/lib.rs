@@ -1,10 +1,23 @@
+pub mod accumulator;
+#[cfg(feature = "window")]
 pub mod app;
+pub mod background;
 pub mod buffer;
 pub mod camera;
 pub mod color;
+pub mod framebuffer;
 pub mod hittable;
+pub mod lights;
+pub mod loaders;
 pub mod materials;
 pub mod math;
+pub mod output;
+pub mod scene;
+pub mod textures;
+pub mod tiles;
+pub mod tonemap;
 
+#[cfg(feature = "window")]
 pub use app::App;
 pub use buffer::Buffer;
+pub use framebuffer::FrameBuffer;
@@ -0,0 +1,27 @@
+pub mod directional;
+pub mod point;
+pub mod spot;
+
+pub use directional::DirectionalLight;
+pub use point::PointLight;
+pub use spot::SpotLight;
+
+use crate::color::Color;
+use crate::math::{Point3, Vector3};
+
+/// Light arriving at a point from a delta light.
+pub struct LightSample {
+    /// Normalized direction from the point towards the light.
+    pub direction: Vector3,
+    /// Distance to the light, infinite for directional lights.
+    pub distance: f32,
+    /// Irradiance on a surface facing the light, falloff included.
+    pub irradiance: Color,
+}
+
+/// Light that can't be hit by rays, evaluated with a shadow ray at every
+/// non-specular bounce.
+pub trait Light: Send + Sync {
+    /// Light arriving at `p`, `None` where the light doesn't reach.
+    fn sample(&self, p: &Point3) -> Option<LightSample>;
+}
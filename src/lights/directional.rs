@@ -0,0 +1,30 @@
+use crate::color::Color;
+use crate::math::{Point3, Vector3};
+
+use super::{Light, LightSample};
+
+/// Parallel light travelling along `direction`, like a distant sun.
+pub struct DirectionalLight {
+    /// Towards the light, the opposite of the travel direction.
+    to_light: Vector3,
+    irradiance: Color,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vector3, irradiance: Color) -> Self {
+        Self {
+            to_light: -direction.normalize(),
+            irradiance,
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _p: &Point3) -> Option<LightSample> {
+        Some(LightSample {
+            direction: self.to_light,
+            distance: f32::INFINITY,
+            irradiance: self.irradiance,
+        })
+    }
+}
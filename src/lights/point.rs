@@ -0,0 +1,37 @@
+use crate::color::Color;
+use crate::math::Point3;
+
+use super::{Light, LightSample};
+
+/// Light emitting `intensity` equally in every direction, with inverse-square
+/// falloff.
+pub struct PointLight {
+    position: Point3,
+    intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Point3, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let offset = self.position - p;
+        let distance_squared = offset.length_squared();
+        if distance_squared == 0.0 {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        Some(LightSample {
+            direction: offset / distance,
+            distance,
+            irradiance: self.intensity / distance_squared,
+        })
+    }
+}
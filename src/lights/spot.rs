@@ -0,0 +1,68 @@
+use crate::color::Color;
+use crate::math::{Point3, Vector3};
+
+use super::{Light, LightSample};
+
+/// Point light restricted to a cone around `direction`.
+pub struct SpotLight {
+    position: Point3,
+    direction: Vector3,
+    intensity: Color,
+    cos_outer: f32,
+    cos_inner: f32,
+}
+
+impl SpotLight {
+    /// `angle` is the cone's half-angle in degrees, the intensity fades out
+    /// smoothly over the last `falloff` degrees towards its edge.
+    pub fn new(
+        position: Point3,
+        direction: Vector3,
+        intensity: Color,
+        angle: f32,
+        falloff: f32,
+    ) -> Self {
+        let outer = angle.clamp(0.0, 180.0);
+        let inner = (outer - falloff.max(0.0)).max(0.0);
+
+        Self {
+            position,
+            direction: direction.normalize(),
+            intensity,
+            cos_outer: outer.to_radians().cos(),
+            cos_inner: inner.to_radians().cos(),
+        }
+    }
+
+    fn falloff(&self, cos_theta: f32) -> f32 {
+        if cos_theta >= self.cos_inner {
+            return 1.0;
+        }
+
+        let t = ((cos_theta - self.cos_outer) / (self.cos_inner - self.cos_outer)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let offset = self.position - p;
+        let distance_squared = offset.length_squared();
+        if distance_squared == 0.0 {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        let direction = offset / distance;
+        let cos_theta = -direction.dot(self.direction);
+        if cos_theta <= self.cos_outer {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            irradiance: self.intensity * self.falloff(cos_theta) / distance_squared,
+        })
+    }
+}
@@ -0,0 +1,3 @@
+pub mod obj;
+
+pub use obj::{ObjError, load_obj};
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use glam::Vec2;
+
+use crate::color::{Color, ColorSpace};
+use crate::hittable::mesh::{MeshData, MeshFace, MeshVertex, TriangleMesh};
+use crate::materials::{
+    Dielectric, DielectricConfig, DiffuseLight, DiffuseLightConfig, Lambertian, LambertianConfig,
+    Material, Metal, MetalConfig,
+};
+use crate::math::Vector3;
+use crate::textures::{ImageTexture, SolidColor, Texture};
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    Texture {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ObjError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ObjError::Texture { path, source } => {
+                write!(f, "{}: failed to load texture: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::Io { source, .. } => Some(source),
+            ObjError::Texture { source, .. } => Some(source),
+            ObjError::Parse { .. } => None,
+        }
+    }
+}
+
+/// Loads a Wavefront OBJ file and the MTL libraries it references.
+///
+/// Polygons are triangulated as fans. Faces without a known `usemtl` use
+/// `default_material`. MTL entries are mapped as follows:
+/// - a non-black `Ke` becomes [`DiffuseLight`];
+/// - a refraction `illum` model, or dissolve below 1 with an explicit `Ni`,
+///   becomes [`Dielectric`] with `Ni`;
+/// - a reflective `illum` model or `Pm > 0` becomes [`Metal`], with fuzz from `Pr` or `Ns`;
+/// - anything else is [`Lambertian`] using `Kd` or `map_Kd`.
+///
+/// MTL colors and textures are sRGB and get converted into `space`.
+pub fn load_obj<P: AsRef<Path>>(
+    path: P,
+    default_material: Arc<dyn Material>,
+    space: ColorSpace,
+) -> Result<TriangleMesh, ObjError> {
+    let path = path.as_ref();
+    let source = read(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut data = MeshData::default();
+    let mut faces = Vec::new();
+    let mut materials = vec![default_material];
+    let mut library: HashMap<String, MtlMaterial> = HashMap::new();
+    let mut loaded: HashMap<String, usize> = HashMap::new();
+    let mut current = 0;
+
+    for (idx, line) in source.lines().enumerate() {
+        let err = |message: String| ObjError::Parse {
+            path: path.to_path_buf(),
+            line: idx + 1,
+            message,
+        };
+
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => data.positions.push(parse_vec3(&args).map_err(err)?),
+            "vn" => data
+                .normals
+                .push(parse_vec3(&args).map_err(err)?.normalize()),
+            "vt" => {
+                // `u [v [w]]`, a missing `v` is 0 and `w` is unused.
+                let uv = parse_floats(&args, args.len().clamp(1, 3)).map_err(err)?;
+                data.uvs
+                    .push(Vec2::new(uv[0], uv.get(1).copied().unwrap_or(0.0)));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(err(format!(
+                        "face needs at least 3 vertices, got {}",
+                        args.len()
+                    )));
+                }
+
+                let corners = args
+                    .iter()
+                    .map(|arg| parse_corner(arg, &data))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(err)?;
+
+                for i in 1..corners.len() - 1 {
+                    faces.push(MeshFace {
+                        vertices: [corners[0], corners[i], corners[i + 1]],
+                        material: current,
+                    });
+                }
+            }
+            "mtllib" => {
+                for name in args {
+                    library.extend(load_mtl(&dir.join(name))?);
+                }
+            }
+            "usemtl" => {
+                let name = args.join(" ");
+                current = match loaded.get(&name) {
+                    Some(&index) => index,
+                    None => match library.get(&name) {
+                        Some(mtl) => {
+                            materials.push(mtl.to_material(dir, space)?);
+                            loaded.insert(name, materials.len() - 1);
+                            materials.len() - 1
+                        }
+                        None => 0,
+                    },
+                };
+            }
+            _ => {}
+        }
+    }
+
+    Ok(TriangleMesh::new(data, faces, materials))
+}
+
+fn read(path: &Path) -> Result<String, ObjError> {
+    fs::read_to_string(path).map_err(|source| ObjError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn parse_floats(args: &[&str], count: usize) -> Result<Vec<f32>, String> {
+    if args.len() < count {
+        return Err(format!("expected {} numbers, got {}", count, args.len()));
+    }
+
+    args[..count]
+        .iter()
+        .map(|a| {
+            a.parse::<f32>()
+                .map_err(|_| format!("invalid number `{}`", a))
+        })
+        .collect()
+}
+
+fn parse_vec3(args: &[&str]) -> Result<Vector3, String> {
+    let v = parse_floats(args, 3)?;
+    Ok(Vector3::new(v[0], v[1], v[2]))
+}
+
+/// Resolves a 1-based (or negative, relative) OBJ index against `len` elements.
+fn resolve_index(token: &str, len: usize) -> Result<usize, String> {
+    let index: i64 = token
+        .parse()
+        .map_err(|_| format!("invalid index `{}`", token))?;
+
+    let resolved = if index < 0 {
+        len as i64 + index
+    } else {
+        index - 1
+    };
+
+    if resolved < 0 || resolved >= len as i64 {
+        return Err(format!("index {} out of range ({} elements)", index, len));
+    }
+
+    Ok(resolved as usize)
+}
+
+/// Parses `v`, `v/vt`, `v//vn` or `v/vt/vn`.
+fn parse_corner(token: &str, data: &MeshData) -> Result<MeshVertex, String> {
+    let mut parts = token.split('/');
+
+    let position = resolve_index(parts.next().unwrap_or(""), data.positions.len())?;
+    let uv = match parts.next() {
+        Some(t) if !t.is_empty() => Some(resolve_index(t, data.uvs.len())?),
+        _ => None,
+    };
+    let normal = match parts.next() {
+        Some(t) if !t.is_empty() => Some(resolve_index(t, data.normals.len())?),
+        _ => None,
+    };
+
+    Ok(MeshVertex {
+        position,
+        normal,
+        uv,
+    })
+}
+
+#[derive(Debug, Clone)]
+struct MtlMaterial {
+    kd: Color,
+    ks: Color,
+    ke: Color,
+    ns: f32,
+    ni: Option<f32>,
+    dissolve: f32,
+    illum: u32,
+    metallic: Option<f32>,
+    roughness: Option<f32>,
+    map_kd: Option<PathBuf>,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        Self {
+            kd: Color::splat(0.8),
+            ks: Color::ZERO,
+            ke: Color::ZERO,
+            ns: 0.0,
+            ni: None,
+            dissolve: 1.0,
+            illum: 2,
+            metallic: None,
+            roughness: None,
+            map_kd: None,
+        }
+    }
+}
+
+impl MtlMaterial {
+    fn to_material(&self, dir: &Path, space: ColorSpace) -> Result<Arc<dyn Material>, ObjError> {
+        if self.ke.max_element() > 0.0 {
+            return Ok(Arc::new(DiffuseLight::new(DiffuseLightConfig {
+                emit: Arc::new(SolidColor::new(space.from_srgb(self.ke))),
+            })));
+        }
+
+        // Exporters write `d` for plain fading too, so it only means glass
+        // when it comes with an index of refraction.
+        let refractive = matches!(self.illum, 4 | 6 | 7 | 9);
+        if refractive || (self.dissolve < 1.0 && self.ni.is_some()) {
+            return Ok(Arc::new(Dielectric::new(DielectricConfig {
+                refraction_index: self.ni.unwrap_or(1.5),
+            })));
+        }
+
+        let albedo: Arc<dyn Texture> = match &self.map_kd {
+            Some(map) => {
+                let path = dir.join(map);
+                let texture = ImageTexture::load(&path, space)
+                    .map_err(|source| ObjError::Texture { path, source })?;
+                Arc::new(texture)
+            }
+            None => Arc::new(SolidColor::new(space.from_srgb(self.kd))),
+        };
+
+        match self.metallic {
+            Some(m) if m > 0.0 => Ok(Arc::new(Metal::new(MetalConfig {
+                albedo,
+                fuzz: self.fuzz(),
+            }))),
+            None if matches!(self.illum, 3 | 5 | 8) => Ok(Arc::new(Metal::new(MetalConfig {
+                albedo: Arc::new(SolidColor::new(space.from_srgb(self.ks))),
+                fuzz: self.fuzz(),
+            }))),
+            _ => Ok(Arc::new(Lambertian::new(LambertianConfig { albedo }))),
+        }
+    }
+
+    /// Uses PBR roughness when given, otherwise converts the Phong exponent.
+    fn fuzz(&self) -> f32 {
+        let fuzz = match self.roughness {
+            Some(r) => r,
+            None => (2.0 / (self.ns + 2.0)).sqrt(),
+        };
+        fuzz.clamp(0.0, 1.0)
+    }
+}
+
+fn load_mtl(path: &Path) -> Result<HashMap<String, MtlMaterial>, ObjError> {
+    let source = read(path)?;
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlMaterial)> = None;
+
+    for (idx, line) in source.lines().enumerate() {
+        let err = |message: String| ObjError::Parse {
+            path: path.to_path_buf(),
+            line: idx + 1,
+            message,
+        };
+
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if let Some((name, mtl)) = current.take() {
+                materials.insert(name, mtl);
+            }
+            current = Some((args.join(" "), MtlMaterial::default()));
+            continue;
+        }
+
+        let Some((_, mtl)) = current.as_mut() else {
+            return Err(err(format!("`{}` before any `newmtl`", keyword)));
+        };
+        let scalar = |args: &[&str]| parse_floats(args, 1).map(|v| v[0]);
+
+        match keyword {
+            "Kd" => mtl.kd = parse_vec3(&args).map_err(err)?,
+            "Ks" => mtl.ks = parse_vec3(&args).map_err(err)?,
+            "Ke" => mtl.ke = parse_vec3(&args).map_err(err)?,
+            "Ns" => mtl.ns = scalar(&args).map_err(err)?,
+            "Ni" => mtl.ni = Some(scalar(&args).map_err(err)?),
+            "d" => mtl.dissolve = scalar(&args).map_err(err)?,
+            "Tr" => mtl.dissolve = 1.0 - scalar(&args).map_err(err)?,
+            "Pm" => mtl.metallic = Some(scalar(&args).map_err(err)?),
+            "Pr" => mtl.roughness = Some(scalar(&args).map_err(err)?),
+            "illum" => {
+                mtl.illum = args
+                    .first()
+                    .and_then(|a| a.parse().ok())
+                    .ok_or_else(|| err("invalid illum model".to_string()))?
+            }
+            "map_Kd" => {
+                let file =
+                    texture_file(&args).ok_or_else(|| err("map_Kd without a file".to_string()))?;
+                mtl.map_kd = Some(PathBuf::from(file));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((name, mtl)) = current {
+        materials.insert(name, mtl);
+    }
+
+    Ok(materials)
+}
+
+/// File name of a texture map statement, after options such as `-s 1 1 1`.
+/// The remaining arguments are joined so that names may contain spaces.
+fn texture_file(args: &[&str]) -> Option<String> {
+    let mut rest = args;
+    while let Some(option) = rest.first().filter(|a| a.starts_with('-')) {
+        // `-o`, `-s` and `-t` take up to three numbers, the others one value.
+        let values = match *option {
+            "-o" | "-s" | "-t" => rest[1..]
+                .iter()
+                .take(3)
+                .take_while(|a| a.parse::<f32>().is_ok())
+                .count(),
+            "-mm" => 2,
+            _ => 1,
+        };
+        rest = rest.get(1 + values..)?;
+    }
+
+    (!rest.is_empty()).then(|| rest.join(" "))
+}
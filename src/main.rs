@@ -1,35 +1,271 @@
 use chrono::{DateTime, Local};
-use image::{ImageFormat, Rgba, RgbaImage};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+#[cfg(feature = "window")]
 use minifb::Window;
+#[cfg(feature = "window")]
+use raytracer::accumulator::Accumulator;
+use raytracer::background::GradientBackground;
 use raytracer::camera::{Camera, CameraConfig};
-use raytracer::color::Color;
-use raytracer::hittable::HittableList;
+use raytracer::color::{Color, ColorSpace};
 use raytracer::hittable::sphere::Sphere;
+use raytracer::hittable::{BvhNode, HittableList};
 use raytracer::materials::{Dielectric, DielectricConfig};
 use raytracer::materials::{Lambertian, LambertianConfig};
-use raytracer::materials::{Metal, MetalConfig};
+use raytracer::materials::{Material, Metal, MetalConfig};
 use raytracer::math::{Point3, rand, rand_rng};
-use raytracer::{App, Buffer};
-use std::rc::Rc;
+use raytracer::output::{self, OutputFormat};
+use raytracer::scene::Scene;
+use raytracer::textures::SolidColor;
+#[cfg(feature = "window")]
+use raytracer::tiles::Tile;
+use raytracer::tiles::TileOrder;
+use raytracer::tonemap::{DisplayTransform, ToneMapper};
+#[cfg(feature = "window")]
+use raytracer::{App, Buffer, FrameBuffer};
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 const WIDTH: f32 = 800.0;
 const ASPECT_RATIO: f32 = 16.0 / 9.0;
 const HEIGHT: f32 = WIDTH / ASPECT_RATIO;
 
+/// Ray Tracing in One Weekend renderer.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Scene file to render, the built-in random spheres scene when omitted
+    scene: Option<PathBuf>,
+
+    /// Image width in pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    width: Option<u32>,
+
+    /// Image height in pixels, derived from the width and aspect ratio when omitted
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    height: Option<u32>,
+
+    /// Samples per pixel
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..))]
+    spp: Option<u32>,
+
+    /// Maximum number of ray bounces
+    #[arg(long)]
+    max_depth: Option<i32>,
+
+    /// Base seed of the per-sample random sequences
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Render threads, 0 uses every available core
+    #[arg(short = 'j', long)]
+    threads: Option<usize>,
+
+    /// Edge length in pixels of the tiles rendered as one unit of work
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    tile_size: Option<usize>,
+
+    /// Order tiles are rendered in (scanline, spiral, hilbert)
+    #[arg(long)]
+    tile_order: Option<TileOrder>,
+
+    /// Output image path
+    #[arg(short, long, default_value = "last_render.png")]
+    output: PathBuf,
+
+    /// Output format (png, jpeg, exr, exr-half, hdr, pfm, ...), inferred from the output extension when omitted
+    #[arg(long)]
+    format: Option<String>,
+
+    /// Tone mapping for 8-bit output and the preview (clamp, reinhard, reinhard-extended, aces, agx)
+    #[arg(long, default_value = "clamp")]
+    tonemap: ToneMapper,
+
+    /// Luminance mapped to white, only with `--tonemap reinhard-extended`
+    #[arg(long, value_parser = positive)]
+    white_point: Option<f32>,
+
+    /// Exposure adjustment in stops
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    exposure: f32,
+
+    /// Render and save the image without opening a preview window, always the
+    /// case in builds without the window feature
+    #[arg(long)]
+    no_window: bool,
+
+    /// How the preview window fills in the image
+    #[cfg(feature = "window")]
+    #[arg(long, value_enum, default_value = "progressive")]
+    preview: PreviewMode,
+}
+
+#[cfg(feature = "window")]
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum PreviewMode {
+    /// Add one sample per pixel to the whole image every frame
+    Progressive,
+    /// Show tiles at full quality as they complete, in tile order
+    Tiles,
+}
+
+impl Args {
+    /// Rejects combinations clap can't express, exiting like a parse error.
+    fn validate(&self) {
+        if self.white_point.is_some()
+            && !matches!(self.tonemap, ToneMapper::ReinhardExtended { .. })
+        {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--white-point only applies to --tonemap reinhard-extended",
+                )
+                .exit();
+        }
+    }
+
+    fn apply(&self, config: &mut CameraConfig) {
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => {
+                config.image_width = width;
+                config.image_height = height;
+                config.aspect_ratio = width as f32 / height as f32;
+            }
+            (Some(width), None) => {
+                config.image_width = width;
+                config.image_height = ((width as f32 / config.aspect_ratio) as u32).max(1);
+            }
+            (None, Some(height)) => {
+                config.image_height = height;
+                config.image_width = ((height as f32 * config.aspect_ratio) as u32).max(1);
+            }
+            (None, None) => {}
+        }
+
+        if let Some(spp) = self.spp {
+            config.samples_per_pixel = spp;
+        }
+        if let Some(max_depth) = self.max_depth {
+            config.max_ray_depth = max_depth;
+        }
+        if let Some(seed) = self.seed {
+            config.seed = seed;
+        }
+        if let Some(threads) = self.threads {
+            config.threads = threads;
+        }
+        if let Some(tile_size) = self.tile_size {
+            config.tile_size = tile_size;
+        }
+        if let Some(tile_order) = self.tile_order {
+            config.tile_order = tile_order;
+        }
+    }
+
+    fn display_transform(&self, working_space: ColorSpace) -> DisplayTransform {
+        let tone_mapper = match (self.tonemap, self.white_point) {
+            (ToneMapper::ReinhardExtended { .. }, Some(white)) => {
+                ToneMapper::ReinhardExtended { white }
+            }
+            (tone_mapper, _) => tone_mapper,
+        };
+
+        DisplayTransform {
+            working_space,
+            exposure: self.exposure,
+            tone_mapper,
+        }
+    }
+
+    fn output_format(&self) -> Result<OutputFormat, String> {
+        match &self.format {
+            Some(format) => OutputFormat::from_extension(format)
+                .ok_or_else(|| format!("unknown output format `{}`", format)),
+            None => OutputFormat::from_path(&self.output).ok_or_else(|| {
+                format!(
+                    "cannot infer output format from `{}`, use --format",
+                    self.output.display()
+                )
+            }),
+        }
+    }
+}
+
+/// Parses a number greater than zero.
+fn positive(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(number) if number > 0.0 && number.is_finite() => Ok(number),
+        Ok(_) => Err("must be a positive number".to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
 struct State {
     camera_config: CameraConfig,
+    world: BvhNode,
+    lights: HittableList,
+    output: PathBuf,
+    format: OutputFormat,
+    display: DisplayTransform,
 }
 
 fn main() {
-    let camera_config = CameraConfig {
+    let args = Args::parse();
+    args.validate();
+
+    let format = args.output_format().unwrap_or_else(|err| fail(&err));
+    let mut scene = match &args.scene {
+        Some(path) => Scene::load(path).unwrap_or_else(|err| fail(&err.to_string())),
+        None => {
+            let mut world = HittableList::new();
+            fill_world(&mut world);
+            Scene {
+                config: default_camera_config(),
+                world,
+                lights: HittableList::new(),
+                color_space: ColorSpace::LinearSrgb,
+            }
+        }
+    };
+    args.apply(&mut scene.config);
+
+    #[cfg(feature = "window")]
+    let (no_window, preview) = (args.no_window, args.preview);
+    let state = State {
+        camera_config: scene.config,
+        world: BvhNode::new(scene.world),
+        lights: scene.lights,
+        display: args.display_transform(scene.color_space),
+        output: args.output,
+        format,
+    };
+
+    #[cfg(feature = "window")]
+    if !no_window {
+        run_window(state, preview);
+        return;
+    }
+
+    render_headless(&state);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
+}
+
+fn default_camera_config() -> CameraConfig {
+    CameraConfig {
         image_width: WIDTH as u32,
         image_height: HEIGHT as u32,
         aspect_ratio: ASPECT_RATIO,
         samples_per_pixel: 100,
         max_ray_depth: 50,
         light_intensity: 0.95,
-        sky_color: Color::new(0.5, 0.7, 1.0),
+        background: Arc::new(GradientBackground::sky(Color::new(0.5, 0.7, 1.0))),
+        delta_lights: Vec::new(),
 
         fov: 20.0,
         look_from: Point3::new(13.0, 2.0, 3.0),
@@ -37,31 +273,171 @@ fn main() {
 
         defocus_angle: 0.6,
         focus_dist: 10.0,
+        shutter_open: 0.0,
+        shutter_close: 0.0,
+
+        threads: 0,
+        tile_size: 32,
+        tile_order: TileOrder::default(),
+        seed: 0,
+    }
+}
+
+/// Preview window state, refined every frame in either [`PreviewMode`].
+#[cfg(feature = "window")]
+struct Preview {
+    state: State,
+    camera: Camera,
+    mode: PreviewMode,
+    accum: Accumulator,
+    fb: FrameBuffer,
+    tiles: Vec<Tile>,
+    next_tile: usize,
+    start: SystemTime,
+    saved: bool,
+}
+
+#[cfg(feature = "window")]
+impl Preview {
+    fn is_done(&self) -> bool {
+        match self.mode {
+            PreviewMode::Progressive => self.accum.passes() >= self.camera.samples_per_pixel(),
+            PreviewMode::Tiles => self.next_tile >= self.tiles.len(),
+        }
+    }
+
+    fn has_started(&self) -> bool {
+        match self.mode {
+            PreviewMode::Progressive => self.accum.passes() > 0,
+            PreviewMode::Tiles => self.next_tile > 0,
+        }
+    }
+
+    /// Progressive mode adds one sample pass, tile mode renders one tile per
+    /// thread.
+    fn step(&mut self) {
+        let state = &self.state;
+        match self.mode {
+            PreviewMode::Progressive => {
+                self.camera
+                    .render_pass(&mut self.accum, &state.world, &state.lights);
+                self.accum.resolve(&mut self.fb);
+            }
+            PreviewMode::Tiles => {
+                let end = (self.next_tile + self.camera.thread_count()).min(self.tiles.len());
+                self.camera.render_region(
+                    &mut self.fb,
+                    &self.tiles[self.next_tile..end],
+                    &state.world,
+                    &state.lights,
+                );
+                self.next_tile = end;
+            }
+        }
+    }
+
+    fn progress(&self) -> String {
+        match self.mode {
+            PreviewMode::Progressive => format!(
+                "{}/{} passes",
+                self.accum.passes(),
+                self.camera.samples_per_pixel()
+            ),
+            PreviewMode::Tiles => format!("{}/{} tiles", self.next_tile, self.tiles.len()),
+        }
+    }
+
+    fn save(&mut self) {
+        let state = &self.state;
+        if let Err(err) = output::save(&self.fb, &state.output, state.format, &state.display) {
+            eprintln!("error: failed to save {}: {}", state.output.display(), err);
+        }
+        self.saved = true;
+    }
+}
+
+#[cfg(feature = "window")]
+fn run_window(state: State, mode: PreviewMode) {
+    let width = state.camera_config.image_width as usize;
+    let height = state.camera_config.image_height as usize;
+    let camera = Camera::new(&state.camera_config);
+
+    let mut preview = Preview {
+        tiles: camera.tiles(),
+        next_tile: 0,
+        camera,
+        mode,
+        accum: Accumulator::new(width, height),
+        fb: FrameBuffer::new(width, height),
+        state,
+        start: SystemTime::now(),
+        saved: false,
     };
-    let mut state = State { camera_config };
 
-    let mut app = App::create(WIDTH as usize, HEIGHT as usize, &mut state, Some(on_init)).unwrap();
-    app.set_fps(120).run_with_callback(render_callback);
+    App::create(width, height, &mut preview, Some(on_init))
+        .unwrap()
+        .set_fps(120)
+        .run_with_callback(render_callback);
+
+    if !preview.saved && preview.has_started() {
+        println!("Window closed after {}", preview.progress());
+        preview.save();
+    }
+}
+
+#[cfg(feature = "window")]
+fn on_init(_bf: &mut Buffer, _window: &Window, preview: &mut Preview) {
+    preview.start = print_started();
 }
 
-fn render_callback(_bf: &mut Buffer, _window: &Window, _state: &mut State) {}
+#[cfg(feature = "window")]
+fn render_callback(bf: &mut Buffer, _window: &Window, preview: &mut Preview) {
+    if preview.is_done() {
+        return;
+    }
+
+    preview.step();
+    preview.fb.to_buffer(bf, &preview.state.display);
 
-fn on_init(bf: &mut Buffer, _window: &Window, state: &mut State) {
+    if preview.is_done() {
+        print_finished(preview.start);
+        preview.save();
+    }
+}
+
+fn render_headless(state: &State) {
     let cam = Camera::new(&state.camera_config);
-    let mut world = HittableList::new();
 
-    fill_world(&mut world);
+    let start = print_started();
+    let result = output::render_to_file(
+        &cam,
+        &state.world,
+        &state.lights,
+        &state.output,
+        state.format,
+        &state.display,
+    );
+    print_finished(start);
 
+    if let Err(err) = result {
+        fail(&format!(
+            "failed to save {}: {}",
+            state.output.display(),
+            err
+        ));
+    }
+}
+
+fn print_started() -> SystemTime {
     let start = SystemTime::now();
     let datetime = DateTime::<Local>::from(start);
     println!("Started at {}", datetime.format("%Y-%m-%d %H:%M:%S.%3f"));
 
-    cam.render(bf, &world);
+    start
+}
 
+fn print_finished(start: SystemTime) {
     let finish = SystemTime::now();
-
-    save_image(bf);
-
     let datetime = DateTime::<Local>::from(finish);
     let duration = finish.duration_since(start).unwrap();
 
@@ -70,13 +446,13 @@ fn on_init(bf: &mut Buffer, _window: &Window, state: &mut State) {
 }
 
 fn fill_world(world: &mut HittableList) {
-    let ground_mat = Rc::new(Lambertian::new(LambertianConfig {
-        albedo: Color::new(0.73, 0.8, 0.87),
+    let ground_mat: Arc<dyn Material> = Arc::new(Lambertian::new(LambertianConfig {
+        albedo: Arc::new(SolidColor::new(Color::new(0.73, 0.8, 0.87))),
     }));
     world.add(Box::new(Sphere::new(
         Point3::new(0.0, -1000.0, 0.0),
         1000.0,
-        Rc::clone(&ground_mat),
+        Arc::clone(&ground_mat),
     )));
 
     for a in -11..11 {
@@ -89,70 +465,55 @@ fn fill_world(world: &mut HittableList) {
                     let albedo =
                         Color::new(rand(), rand(), rand()) * Color::new(rand(), rand(), rand());
 
-                    let mat = Rc::new(Lambertian::new(LambertianConfig { albedo }));
-                    world.add(Box::new(Sphere::new(center, 0.2, Rc::clone(&mat))));
+                    let mat: Arc<dyn Material> = Arc::new(Lambertian::new(LambertianConfig {
+                        albedo: Arc::new(SolidColor::new(albedo)),
+                    }));
+                    world.add(Box::new(Sphere::new(center, 0.2, Arc::clone(&mat))));
                 } else if r_mat < 0.95 {
                     let albedo =
                         Color::new(rand(), rand(), rand()) * Color::new(rand(), rand(), rand());
                     let fuzz = rand_rng(0.0, 0.5);
 
-                    let mat = Rc::new(Metal::new(MetalConfig { albedo, fuzz }));
-                    world.add(Box::new(Sphere::new(center, 0.2, Rc::clone(&mat))));
+                    let mat: Arc<dyn Material> = Arc::new(Metal::new(MetalConfig {
+                        albedo: Arc::new(SolidColor::new(albedo)),
+                        fuzz,
+                    }));
+                    world.add(Box::new(Sphere::new(center, 0.2, Arc::clone(&mat))));
                 } else {
-                    let mat = Rc::new(Dielectric::new(DielectricConfig {
+                    let mat: Arc<dyn Material> = Arc::new(Dielectric::new(DielectricConfig {
                         refraction_index: 1.5,
                     }));
-                    world.add(Box::new(Sphere::new(center, 0.2, Rc::clone(&mat))));
+                    world.add(Box::new(Sphere::new(center, 0.2, Arc::clone(&mat))));
                 }
             }
         }
     }
 
-    let mat1 = Rc::new(Dielectric::new(DielectricConfig {
+    let mat1: Arc<dyn Material> = Arc::new(Dielectric::new(DielectricConfig {
         refraction_index: 1.5,
     }));
     world.add(Box::new(Sphere::new(
         Point3::new(0.0, 1.0, 0.0),
         1.0,
-        Rc::clone(&mat1),
+        Arc::clone(&mat1),
     )));
 
-    let mat2 = Rc::new(Lambertian::new(LambertianConfig {
-        albedo: Color::new(0.4, 0.2, 0.1),
+    let mat2: Arc<dyn Material> = Arc::new(Lambertian::new(LambertianConfig {
+        albedo: Arc::new(SolidColor::new(Color::new(0.4, 0.2, 0.1))),
     }));
     world.add(Box::new(Sphere::new(
         Point3::new(-4.0, 1.0, 0.0),
         1.0,
-        Rc::clone(&mat2),
+        Arc::clone(&mat2),
     )));
 
-    let mat3 = Rc::new(Metal::new(MetalConfig {
-        albedo: Color::new(0.7, 0.7, 0.5),
+    let mat3: Arc<dyn Material> = Arc::new(Metal::new(MetalConfig {
+        albedo: Arc::new(SolidColor::new(Color::new(0.7, 0.7, 0.5))),
         fuzz: 0.0,
     }));
     world.add(Box::new(Sphere::new(
         Point3::new(4.0, 1.0, 0.0),
         1.0,
-        Rc::clone(&mat3),
+        Arc::clone(&mat3),
     )));
 }
-
-fn save_image(bf: &Buffer) {
-    let mut img = RgbaImage::new(WIDTH as u32, HEIGHT as u32);
-    for y in 0..HEIGHT as u32 {
-        for x in 0..WIDTH as u32 {
-            let idx = (y * WIDTH as u32 + x) as usize;
-            let pixel_value = bf.buffer()[idx];
-
-            let r = ((pixel_value >> 16) & 0xFF) as u8;
-            let g = ((pixel_value >> 8) & 0xFF) as u8;
-            let b = (pixel_value & 0xFF) as u8;
-            let a = ((pixel_value >> 24) & 0xFF) as u8;
-
-            img.put_pixel(x, y, Rgba([r, g, b, a]));
-        }
-    }
-
-    img.save_with_format("last_render.png", ImageFormat::Png)
-        .unwrap();
-}
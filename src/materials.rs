@@ -1,13 +1,57 @@
+pub mod conductor;
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
+pub mod microfacet;
+pub mod principled;
+pub mod rough_dielectric;
 
+pub use conductor::{Conductor, ConductorConfig, ConductorPreset};
 pub use dielectric::{Dielectric, DielectricConfig};
+pub use diffuse_light::{DiffuseLight, DiffuseLightConfig};
+pub use isotropic::{Isotropic, IsotropicConfig};
 pub use lambertian::{Lambertian, LambertianConfig};
 pub use metal::{Metal, MetalConfig};
+pub use principled::{Principled, PrincipledConfig, Scalar};
+pub use rough_dielectric::{RoughDielectric, RoughDielectricConfig};
 
-use crate::{color::Color, hittable::HitRecord, math::Ray};
+use crate::{
+    color::Color,
+    hittable::HitRecord,
+    math::{Ray, Vector3},
+};
 
-pub trait Material: Sync {
-    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<(Color, Ray)>;
+/// Outgoing ray chosen by [`Material::scatter`].
+pub struct ScatterRecord {
+    /// Path throughput weight, the BSDF times cosine divided by `pdf`.
+    pub attenuation: Color,
+    pub ray: Ray,
+    /// Solid angle density of `ray`'s direction, `None` for specular lobes
+    /// that only a BSDF sample can find.
+    pub pdf: Option<f32>,
+}
+
+/// Directions passed to [`Material::bsdf_eval`] and [`Material::pdf`] are
+/// normalized and point away from the surface: `wo` towards the viewer,
+/// `wi` towards the incoming light.
+pub trait Material: Send + Sync {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord>;
+
+    /// BSDF times `|cos|` of `wi` against the shading normal, black for
+    /// specular materials.
+    fn bsdf_eval(&self, _hit: &HitRecord, _wi: &Vector3, _wo: &Vector3) -> Color {
+        Color::ZERO
+    }
+
+    /// Density of [`Material::scatter`] choosing `wi` when seen from `wo`.
+    fn pdf(&self, _hit: &HitRecord, _wi: &Vector3, _wo: &Vector3) -> f32 {
+        0.0
+    }
+
+    /// Radiance emitted by the surface at the hit point, black for non-emitters.
+    fn emitted(&self, _hit: &HitRecord) -> Color {
+        Color::ZERO
+    }
 }
@@ -0,0 +1,128 @@
+use serde::Deserialize;
+
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::math::{Onb, Ray, Vector3, rand};
+
+use super::microfacet::{Ggx, fresnel_conductor, reflect};
+use super::{Material, ScatterRecord};
+
+/// Rough metal with a GGX microfacet distribution and complex IOR Fresnel.
+pub struct Conductor {
+    eta: Color,
+    k: Color,
+    distribution: Ggx,
+}
+
+pub struct ConductorConfig {
+    /// Real part of the index of refraction, per RGB channel.
+    pub eta: Color,
+    /// Extinction coefficient, per RGB channel.
+    pub k: Color,
+    /// Perceptual roughness in [0, 1], `0` is a perfect mirror.
+    pub roughness: f32,
+}
+
+/// Measured complex IORs sampled at roughly 650, 550 and 450 nm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConductorPreset {
+    Gold,
+    Copper,
+    Aluminum,
+    Silver,
+}
+
+impl ConductorPreset {
+    pub fn config(&self, roughness: f32) -> ConductorConfig {
+        let (eta, k) = match self {
+            ConductorPreset::Gold => ([0.143, 0.374, 1.442], [3.983, 2.385, 1.603]),
+            ConductorPreset::Copper => ([0.200, 0.924, 1.102], [3.912, 2.452, 2.142]),
+            ConductorPreset::Aluminum => ([1.657, 0.880, 0.521], [9.224, 6.270, 4.837]),
+            ConductorPreset::Silver => ([0.155, 0.117, 0.138], [4.828, 3.122, 2.147]),
+        };
+
+        ConductorConfig {
+            eta: Color::from_array(eta),
+            k: Color::from_array(k),
+            roughness,
+        }
+    }
+}
+
+impl Conductor {
+    pub fn new(config: ConductorConfig) -> Self {
+        Self {
+            eta: config.eta,
+            k: config.k,
+            distribution: Ggx::from_roughness(config.roughness),
+        }
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let frame = Onb::new(hit.normal());
+        let wo = frame.to_local(&-ray.dir().normalize());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        if self.distribution.is_smooth() {
+            let wi = Vector3::new(-wo.x, -wo.y, wo.z);
+            return Some(ScatterRecord {
+                attenuation: fresnel_conductor(wo.z, &self.eta, &self.k),
+                ray: Ray::with_time(*hit.p(), frame.local(&wi), ray.time()),
+                pdf: None,
+            });
+        }
+
+        let h = self.distribution.sample_visible_normal(&wo, rand(), rand());
+        let wi = reflect(&wo, &h);
+        if wi.z <= 0.0 {
+            return None;
+        }
+
+        // f cos / pdf with the D and G1 terms of the VNDF cancelling out.
+        let fresnel = fresnel_conductor(wo.dot(h), &self.eta, &self.k);
+        let weight = self.distribution.g2(&wo, &wi) / self.distribution.g1(&wo);
+        let pdf = self.distribution.visible_normal_pdf(&wo, &h) / (4.0 * wo.dot(h));
+
+        Some(ScatterRecord {
+            attenuation: fresnel * weight,
+            ray: Ray::with_time(*hit.p(), frame.local(&wi), ray.time()),
+            pdf: Some(pdf),
+        })
+    }
+
+    fn bsdf_eval(&self, hit: &HitRecord, wi: &Vector3, wo: &Vector3) -> Color {
+        if self.distribution.is_smooth() {
+            return Color::ZERO;
+        }
+
+        let frame = Onb::new(hit.normal());
+        let (wi, wo) = (frame.to_local(wi), frame.to_local(wo));
+        if wi.z <= 0.0 || wo.z <= 0.0 {
+            return Color::ZERO;
+        }
+
+        let h = (wi + wo).normalize();
+        let fresnel = fresnel_conductor(wo.dot(h), &self.eta, &self.k);
+        fresnel * self.distribution.d(&h) * self.distribution.g2(&wo, &wi) / (4.0 * wo.z)
+    }
+
+    fn pdf(&self, hit: &HitRecord, wi: &Vector3, wo: &Vector3) -> f32 {
+        if self.distribution.is_smooth() {
+            return 0.0;
+        }
+
+        let frame = Onb::new(hit.normal());
+        let (wi, wo) = (frame.to_local(wi), frame.to_local(wo));
+        if wi.z <= 0.0 || wo.z <= 0.0 {
+            return 0.0;
+        }
+
+        let h = (wi + wo).normalize();
+        self.distribution.visible_normal_pdf(&wo, &h) / (4.0 * wo.dot(h))
+    }
+}
@@ -1,8 +1,8 @@
 use crate::color::Color;
-use crate::math::{Ray, Vector3, rand, vec3_reflect, vec3_refract_with_cos};
+use crate::math::{Ray, rand, vec3_reflect, vec3_refract_with_cos};
 use std::ops::Neg;
 
-use super::Material;
+use super::{Material, ScatterRecord};
 
 pub struct Dielectric {
     refraction_index: f32,
@@ -28,7 +28,7 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, hit: &crate::hittable::HitRecord) -> Option<(Color, Ray)> {
+    fn scatter(&self, ray: &Ray, hit: &crate::hittable::HitRecord) -> Option<ScatterRecord> {
         let ri = if hit.front_face() {
             1.0 / self.refraction_index
         } else {
@@ -40,14 +40,16 @@ impl Material for Dielectric {
         let sin = (1.0 - cos * cos).sqrt();
 
         let cannot_refract = ri * sin > 1.0;
-        let dir: Vector3;
-
-        if cannot_refract || Dielectric::reflectance(cos, ri) > rand() {
-            dir = vec3_reflect(&unit_dir, &hit.normal());
+        let dir = if cannot_refract || Dielectric::reflectance(cos, ri) > rand() {
+            vec3_reflect(&unit_dir, hit.normal())
         } else {
-            dir = vec3_refract_with_cos(&unit_dir, &hit.normal(), ri, cos);
-        }
+            vec3_refract_with_cos(&unit_dir, hit.normal(), ri, cos)
+        };
 
-        Some((Color::new(1.0, 1.0, 1.0), Ray::new(*hit.p(), dir)))
+        Some(ScatterRecord {
+            attenuation: Color::new(1.0, 1.0, 1.0),
+            ray: Ray::with_time(*hit.p(), dir, ray.time()),
+            pdf: None,
+        })
     }
 }
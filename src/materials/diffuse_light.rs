@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::math::Ray;
+use crate::textures::Texture;
+
+use super::{Material, ScatterRecord};
+
+/// Emits `emit` from the front face of the surface and does not scatter.
+pub struct DiffuseLight {
+    emit: Arc<dyn Texture>,
+}
+
+pub struct DiffuseLightConfig {
+    pub emit: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new(config: DiffuseLightConfig) -> Self {
+        Self { emit: config.emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray: &Ray, _hit: &HitRecord) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, hit: &HitRecord) -> Color {
+        if hit.front_face() {
+            self.emit.value(hit.u(), hit.v(), hit.p())
+        } else {
+            Color::ZERO
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::math::{Ray, Vector3, rand_unit_vec3};
+use crate::textures::Texture;
+
+use super::{Material, ScatterRecord};
+
+/// Phase function scattering uniformly over the sphere, for volumes.
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+}
+
+pub struct IsotropicConfig {
+    pub albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(config: IsotropicConfig) -> Self {
+        Self {
+            albedo: config.albedo,
+        }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        Some(ScatterRecord {
+            attenuation: self.albedo.value(hit.u(), hit.v(), hit.p()),
+            ray: Ray::with_time(*hit.p(), rand_unit_vec3(), ray.time()),
+            pdf: Some(1.0 / (4.0 * PI)),
+        })
+    }
+
+    /// The phase function has no cosine term.
+    fn bsdf_eval(&self, hit: &HitRecord, _wi: &Vector3, _wo: &Vector3) -> Color {
+        self.albedo.value(hit.u(), hit.v(), hit.p()) / (4.0 * PI)
+    }
+
+    fn pdf(&self, _hit: &HitRecord, _wi: &Vector3, _wo: &Vector3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+}
@@ -1,14 +1,19 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
 use crate::color::Color;
-use crate::math::{Ray, Vector3Ext, rand_unit_vec3};
+use crate::hittable::HitRecord;
+use crate::math::{Onb, Ray, Vector3, rand_cosine_direction};
+use crate::textures::Texture;
 
-use super::Material;
+use super::{Material, ScatterRecord};
 
 pub struct Lambertian {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
 }
 
 pub struct LambertianConfig {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
@@ -20,12 +25,24 @@ impl Lambertian {
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, hit: &crate::hittable::HitRecord) -> Option<(Color, Ray)> {
-        let mut scatter_direction = hit.normal() + rand_unit_vec3();
-        if scatter_direction.near_zero() {
-            scatter_direction = *hit.normal();
-        }
+    /// Samples the cosine-weighted hemisphere, so the weight is just the albedo.
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let direction = Onb::new(hit.normal()).local(&rand_cosine_direction());
+        let pdf = hit.normal().dot(direction).max(0.0) / PI;
+
+        Some(ScatterRecord {
+            attenuation: self.albedo.value(hit.u(), hit.v(), hit.p()),
+            ray: Ray::with_time(*hit.p(), direction, ray.time()),
+            pdf: Some(pdf),
+        })
+    }
+
+    fn bsdf_eval(&self, hit: &HitRecord, wi: &Vector3, _wo: &Vector3) -> Color {
+        let cos_theta = hit.normal().dot(*wi).max(0.0);
+        self.albedo.value(hit.u(), hit.v(), hit.p()) * cos_theta / PI
+    }
 
-        Some((self.albedo, Ray::new(*hit.p(), scatter_direction)))
+    fn pdf(&self, hit: &HitRecord, wi: &Vector3, _wo: &Vector3) -> f32 {
+        hit.normal().dot(*wi).max(0.0) / PI
     }
 }
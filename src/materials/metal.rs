@@ -1,14 +1,16 @@
-use super::Material;
-use crate::color::Color;
+use std::sync::Arc;
+
+use super::{Material, ScatterRecord};
 use crate::math::{Ray, rand_unit_vec3, vec3_reflect};
+use crate::textures::Texture;
 
 pub struct Metal {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
     fuzz: f32,
 }
 
 pub struct MetalConfig {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture>,
     pub fuzz: f32,
 }
 
@@ -22,12 +24,16 @@ impl Metal {
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: &Ray, hit: &crate::hittable::HitRecord) -> Option<(Color, Ray)> {
+    fn scatter(&self, ray: &Ray, hit: &crate::hittable::HitRecord) -> Option<ScatterRecord> {
         let reflected = vec3_reflect(ray.dir(), hit.normal());
         let reflected = reflected.normalize() + (self.fuzz * rand_unit_vec3());
-        let scattered = Ray::new(*hit.p(), reflected);
+        let scattered = Ray::with_time(*hit.p(), reflected, ray.time());
         if scattered.dir().dot(*hit.normal()) > 0.0 {
-            Some((self.albedo, scattered))
+            Some(ScatterRecord {
+                attenuation: self.albedo.value(hit.u(), hit.v(), hit.p()),
+                ray: scattered,
+                pdf: None,
+            })
         } else {
             None
         }
@@ -0,0 +1,166 @@
+//! GGX (Trowbridge-Reitz) microfacet helpers shared by the rough materials.
+//!
+//! Directions are in the local shading frame, with the normal along +Z.
+
+use std::f32::consts::PI;
+
+use crate::color::Color;
+use crate::math::Vector3;
+
+/// Below this `alpha` surfaces are treated as perfectly smooth.
+pub const SMOOTH_ALPHA: f32 = 1e-3;
+
+/// Isotropic GGX distribution.
+#[derive(Debug, Clone, Copy)]
+pub struct Ggx {
+    alpha: f32,
+}
+
+impl Ggx {
+    /// Uses the perceptual `roughness` squared as `alpha`.
+    pub fn from_roughness(roughness: f32) -> Self {
+        let roughness = roughness.clamp(0.0, 1.0);
+        Self {
+            alpha: roughness * roughness,
+        }
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    pub fn is_smooth(&self) -> bool {
+        self.alpha < SMOOTH_ALPHA
+    }
+
+    /// Normal distribution `D(h)`.
+    pub fn d(&self, h: &Vector3) -> f32 {
+        if h.z <= 0.0 {
+            return 0.0;
+        }
+
+        let a2 = self.alpha * self.alpha;
+        let t = h.z * h.z * (a2 - 1.0) + 1.0;
+        a2 / (PI * t * t)
+    }
+
+    /// Smith `Λ(w)`.
+    pub fn lambda(&self, w: &Vector3) -> f32 {
+        let cos2 = w.z * w.z;
+        if cos2 == 0.0 {
+            return f32::INFINITY;
+        }
+
+        let tan2 = (w.x * w.x + w.y * w.y) / cos2;
+        ((1.0 + self.alpha * self.alpha * tan2).sqrt() - 1.0) / 2.0
+    }
+
+    pub fn g1(&self, w: &Vector3) -> f32 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Height-correlated masking-shadowing.
+    pub fn g2(&self, wo: &Vector3, wi: &Vector3) -> f32 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Density of [`Ggx::sample_visible_normal`] returning `h` for `wo`.
+    pub fn visible_normal_pdf(&self, wo: &Vector3, h: &Vector3) -> f32 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+
+        self.g1(wo) * wo.dot(*h).max(0.0) * self.d(h) / wo.z
+    }
+
+    /// Samples a microfacet normal visible from `wo` (Heitz 2018), `u1` and
+    /// `u2` are uniform in [0, 1).
+    pub fn sample_visible_normal(&self, wo: &Vector3, u1: f32, u2: f32) -> Vector3 {
+        let vh = Vector3::new(self.alpha * wo.x, self.alpha * wo.y, wo.z).normalize();
+
+        let len2 = vh.x * vh.x + vh.y * vh.y;
+        let t1 = if len2 > 0.0 {
+            Vector3::new(-vh.y, vh.x, 0.0) / len2.sqrt()
+        } else {
+            Vector3::X
+        };
+        let t2 = vh.cross(t1);
+
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+        Vector3::new(self.alpha * nh.x, self.alpha * nh.y, nh.z.max(1e-6)).normalize()
+    }
+}
+
+pub fn reflect(wo: &Vector3, h: &Vector3) -> Vector3 {
+    2.0 * wo.dot(*h) * h - wo
+}
+
+/// Refracts `wo` through a facet with normal `h` on its side, `eta` is the
+/// ratio of the transmitted to the incident index. `None` on total internal
+/// reflection.
+pub fn refract(wo: &Vector3, h: &Vector3, eta: f32) -> Option<Vector3> {
+    let cos_i = wo.dot(*h);
+    let sin2_t = (1.0 - cos_i * cos_i).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(-wo / eta + (cos_i / eta - cos_t) * h)
+}
+
+/// Unpolarized Fresnel reflectance of a dielectric interface.
+pub fn fresnel_dielectric(cos_i: f32, eta: f32) -> f32 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let r_parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let r_perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    (r_parallel * r_parallel + r_perpendicular * r_perpendicular) / 2.0
+}
+
+/// Fresnel reflectance of a conductor with complex index `eta + i k`, per channel.
+pub fn fresnel_conductor(cos_i: f32, eta: &Color, k: &Color) -> Color {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let channel = |eta: f32, k: f32| {
+        let cos2 = cos_i * cos_i;
+        let sin2 = 1.0 - cos2;
+        let eta2 = eta * eta;
+        let k2 = k * k;
+
+        let t0 = eta2 - k2 - sin2;
+        let a2b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+        let t1 = a2b2 + cos2;
+        let a = (0.5 * (a2b2 + t0)).max(0.0).sqrt();
+        let t2 = 2.0 * cos_i * a;
+        let rs = (t1 - t2) / (t1 + t2);
+
+        let t3 = cos2 * a2b2 + sin2 * sin2;
+        let t4 = t2 * sin2;
+        let rp = rs * (t3 - t4) / (t3 + t4);
+
+        0.5 * (rp + rs)
+    };
+
+    Color::new(
+        channel(eta.x, k.x),
+        channel(eta.y, k.y),
+        channel(eta.z, k.z),
+    )
+}
+
+/// Schlick's approximation of the reflectance with normal incidence `f0`.
+pub fn fresnel_schlick(f0: Color, cos_i: f32) -> Color {
+    let m = (1.0 - cos_i.clamp(0.0, 1.0)).powi(5);
+    f0 + (Color::ONE - f0) * m
+}
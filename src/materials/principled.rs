@@ -0,0 +1,271 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::color::{Color, luminance};
+use crate::hittable::HitRecord;
+use crate::math::{Onb, Ray, Vector3, rand, rand_cosine_direction};
+use crate::textures::Texture;
+
+use super::microfacet::{Ggx, fresnel_schlick, reflect};
+use super::{Material, ScatterRecord, rough_dielectric};
+
+/// Lowest roughness the specular lobes use, keeps them away from delta lobes
+/// so light sampling still applies.
+const MIN_ROUGHNESS: f32 = 0.05;
+
+/// Scalar material parameter, constant or read from the first channel of a texture.
+#[derive(Clone)]
+pub enum Scalar {
+    Constant(f32),
+    Texture(Arc<dyn Texture>),
+}
+
+impl Scalar {
+    fn value(&self, hit: &HitRecord) -> f32 {
+        match self {
+            Scalar::Constant(value) => *value,
+            Scalar::Texture(texture) => texture.value(hit.u(), hit.v(), hit.p()).x,
+        }
+    }
+}
+
+impl From<f32> for Scalar {
+    fn from(value: f32) -> Self {
+        Scalar::Constant(value)
+    }
+}
+
+/// Disney-style uber material layering diffuse, sheen, specular, clearcoat
+/// and transmission lobes.
+pub struct Principled {
+    base_color: Arc<dyn Texture>,
+    metallic: Scalar,
+    roughness: Scalar,
+    specular: Scalar,
+    sheen: Scalar,
+    clearcoat: Scalar,
+    clearcoat_roughness: Scalar,
+    transmission: Scalar,
+    ior: Scalar,
+}
+
+pub struct PrincipledConfig {
+    pub base_color: Arc<dyn Texture>,
+    /// Blends from dielectric (0) to conductor tinted by `base_color` (1).
+    pub metallic: Scalar,
+    pub roughness: Scalar,
+    /// Dielectric reflectance, `0.5` is an F0 of 4%.
+    pub specular: Scalar,
+    /// White grazing retro-reflection for cloth.
+    pub sheen: Scalar,
+    /// Strength of a white GGX coat on top of the other lobes.
+    pub clearcoat: Scalar,
+    pub clearcoat_roughness: Scalar,
+    /// Fraction of the dielectric part that is rough glass instead of diffuse.
+    pub transmission: Scalar,
+    /// Index of refraction of the transmission lobe.
+    pub ior: Scalar,
+}
+
+impl PrincipledConfig {
+    /// Plain dielectric with the defaults of the original Disney model.
+    pub fn new(base_color: Arc<dyn Texture>) -> Self {
+        Self {
+            base_color,
+            metallic: 0.0.into(),
+            roughness: 0.5.into(),
+            specular: 0.5.into(),
+            sheen: 0.0.into(),
+            clearcoat: 0.0.into(),
+            clearcoat_roughness: 0.1.into(),
+            transmission: 0.0.into(),
+            ior: 1.5.into(),
+        }
+    }
+}
+
+/// Parameters evaluated at one hit point.
+struct Lobes {
+    base_color: Color,
+    metallic: f32,
+    sheen: f32,
+    clearcoat: f32,
+    transmission: f32,
+    eta: f32,
+    specular_f0: Color,
+    specular: Ggx,
+    coat: Ggx,
+}
+
+impl Lobes {
+    fn diffuse_weight(&self) -> f32 {
+        (1.0 - self.metallic) * (1.0 - self.transmission)
+    }
+
+    fn glass_weight(&self) -> f32 {
+        (1.0 - self.metallic) * self.transmission
+    }
+
+    fn coat_weight(&self) -> f32 {
+        0.25 * self.clearcoat
+    }
+
+    /// Probabilities of sampling the diffuse, specular, clearcoat and glass
+    /// lobes, roughly following their reflectance towards `wo`.
+    fn selection(&self, wo: &Vector3) -> [f32; 4] {
+        let weights = [
+            self.diffuse_weight() * (luminance(self.base_color) + self.sheen).max(0.05),
+            (1.0 - self.glass_weight()) * luminance(fresnel_schlick(self.specular_f0, wo.z)),
+            self.coat_weight() * fresnel_schlick(Color::splat(0.04), wo.z).x,
+            self.glass_weight(),
+        ];
+
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            return [1.0, 0.0, 0.0, 0.0];
+        }
+        weights.map(|w| w / total)
+    }
+
+    fn eval(&self, wi: &Vector3, wo: &Vector3) -> Color {
+        let mut f = Color::ZERO;
+
+        if self.glass_weight() > 0.0 {
+            let (reflection, transmission) =
+                rough_dielectric::eval(&self.specular, self.eta, wi, wo);
+            f += self.glass_weight() * (reflection + transmission * self.base_color);
+        }
+
+        if wi.z <= 0.0 || wo.z <= 0.0 {
+            return f;
+        }
+
+        let h = (wi + wo).normalize();
+        let cos_d = wi.dot(h);
+
+        // Burley diffuse with roughness-dependent retro-reflection, plus sheen.
+        let fd90 = 0.5 + 2.0 * self.specular.alpha().sqrt() * cos_d * cos_d;
+        let fl = 1.0 + (fd90 - 1.0) * (1.0 - wi.z).powi(5);
+        let fv = 1.0 + (fd90 - 1.0) * (1.0 - wo.z).powi(5);
+        let diffuse = self.base_color / PI * fl * fv;
+        let sheen = Color::splat(self.sheen * (1.0 - cos_d).powi(5));
+        f += self.diffuse_weight() * diffuse * wi.z + (1.0 - self.metallic) * sheen * wi.z;
+
+        let specular = fresnel_schlick(self.specular_f0, cos_d)
+            * self.specular.d(&h)
+            * self.specular.g2(wo, wi)
+            / (4.0 * wo.z);
+        f += (1.0 - self.glass_weight()) * specular;
+
+        if self.clearcoat > 0.0 {
+            let coat =
+                fresnel_schlick(Color::splat(0.04), cos_d) * self.coat.d(&h) * self.coat.g2(wo, wi)
+                    / (4.0 * wo.z);
+            f += self.coat_weight() * coat;
+        }
+
+        f
+    }
+
+    fn pdf(&self, wi: &Vector3, wo: &Vector3) -> f32 {
+        let [diffuse, specular, coat, glass] = self.selection(wo);
+        let mut pdf = glass * rough_dielectric::pdf(&self.specular, self.eta, wi, wo);
+
+        if wi.z > 0.0 && wo.z > 0.0 {
+            let h = (wi + wo).normalize();
+            pdf += diffuse * wi.z / PI;
+            pdf += specular * self.specular.visible_normal_pdf(wo, &h) / (4.0 * wo.dot(h));
+            pdf += coat * self.coat.visible_normal_pdf(wo, &h) / (4.0 * wo.dot(h));
+        }
+
+        pdf
+    }
+
+    fn sample(&self, wo: &Vector3) -> Option<Vector3> {
+        let [diffuse, specular, coat, _] = self.selection(wo);
+        let u = rand();
+
+        let wi = if u < diffuse {
+            rand_cosine_direction()
+        } else if u < diffuse + specular {
+            reflect(wo, &self.specular.sample_visible_normal(wo, rand(), rand()))
+        } else if u < diffuse + specular + coat {
+            reflect(wo, &self.coat.sample_visible_normal(wo, rand(), rand()))
+        } else {
+            return rough_dielectric::sample(&self.specular, self.eta, wo);
+        };
+
+        Some(wi).filter(|wi| wi.z > 0.0)
+    }
+}
+
+impl Principled {
+    pub fn new(config: PrincipledConfig) -> Self {
+        Self {
+            base_color: config.base_color,
+            metallic: config.metallic,
+            roughness: config.roughness,
+            specular: config.specular,
+            sheen: config.sheen,
+            clearcoat: config.clearcoat,
+            clearcoat_roughness: config.clearcoat_roughness,
+            transmission: config.transmission,
+            ior: config.ior,
+        }
+    }
+
+    fn lobes(&self, hit: &HitRecord) -> Lobes {
+        let base_color = self.base_color.value(hit.u(), hit.v(), hit.p());
+        let metallic = self.metallic.value(hit).clamp(0.0, 1.0);
+        let roughness = self.roughness.value(hit).max(MIN_ROUGHNESS);
+        let dielectric_f0 = Color::splat(0.08 * self.specular.value(hit).clamp(0.0, 1.0));
+        let ior = self.ior.value(hit).max(1.0 + 1e-3);
+
+        Lobes {
+            base_color,
+            metallic,
+            sheen: self.sheen.value(hit).max(0.0),
+            clearcoat: self.clearcoat.value(hit).clamp(0.0, 1.0),
+            transmission: self.transmission.value(hit).clamp(0.0, 1.0),
+            eta: if hit.front_face() { ior } else { 1.0 / ior },
+            specular_f0: dielectric_f0.lerp(base_color, metallic),
+            specular: Ggx::from_roughness(roughness),
+            coat: Ggx::from_roughness(self.clearcoat_roughness.value(hit).max(MIN_ROUGHNESS)),
+        }
+    }
+}
+
+impl Material for Principled {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let frame = Onb::new(hit.normal());
+        let wo = frame.to_local(&-ray.dir().normalize());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let lobes = self.lobes(hit);
+        let wi = lobes.sample(&wo)?;
+        let pdf = lobes.pdf(&wi, &wo);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(ScatterRecord {
+            attenuation: lobes.eval(&wi, &wo) / pdf,
+            ray: Ray::with_time(*hit.p(), frame.local(&wi), ray.time()),
+            pdf: Some(pdf),
+        })
+    }
+
+    fn bsdf_eval(&self, hit: &HitRecord, wi: &Vector3, wo: &Vector3) -> Color {
+        let frame = Onb::new(hit.normal());
+        self.lobes(hit)
+            .eval(&frame.to_local(wi), &frame.to_local(wo))
+    }
+
+    fn pdf(&self, hit: &HitRecord, wi: &Vector3, wo: &Vector3) -> f32 {
+        let frame = Onb::new(hit.normal());
+        self.lobes(hit)
+            .pdf(&frame.to_local(wi), &frame.to_local(wo))
+    }
+}
@@ -0,0 +1,168 @@
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::math::{Onb, Ray, Vector3, rand};
+
+use super::microfacet::{Ggx, fresnel_dielectric, reflect, refract};
+use super::{Material, ScatterRecord};
+
+/// Frosted glass: GGX microfacet reflection and transmission (Walter et al. 2007).
+pub struct RoughDielectric {
+    refraction_index: f32,
+    distribution: Ggx,
+}
+
+pub struct RoughDielectricConfig {
+    pub refraction_index: f32,
+    /// Perceptual roughness in [0, 1], `0` behaves like [`super::Dielectric`].
+    pub roughness: f32,
+}
+
+impl RoughDielectric {
+    pub fn new(config: RoughDielectricConfig) -> Self {
+        Self {
+            refraction_index: config.refraction_index,
+            distribution: Ggx::from_roughness(config.roughness),
+        }
+    }
+
+    /// Ratio of the index on the far side of the surface to the near side.
+    fn eta(&self, hit: &HitRecord) -> f32 {
+        if hit.front_face() {
+            self.refraction_index
+        } else {
+            1.0 / self.refraction_index
+        }
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let frame = Onb::new(hit.normal());
+        let wo = frame.to_local(&-ray.dir().normalize());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let eta = self.eta(hit);
+        if self.distribution.is_smooth() {
+            let fresnel = fresnel_dielectric(wo.z, eta);
+            let wi = if rand() < fresnel {
+                reflect(&wo, &Vector3::Z)
+            } else {
+                refract(&wo, &Vector3::Z, eta)?
+            };
+
+            return Some(ScatterRecord {
+                attenuation: Color::ONE,
+                ray: Ray::with_time(*hit.p(), frame.local(&wi), ray.time()),
+                pdf: None,
+            });
+        }
+
+        let wi = sample(&self.distribution, eta, &wo)?;
+        let pdf = pdf(&self.distribution, eta, &wi, &wo);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let (reflection, transmission) = eval(&self.distribution, eta, &wi, &wo);
+        Some(ScatterRecord {
+            attenuation: Color::splat((reflection + transmission) / pdf),
+            ray: Ray::with_time(*hit.p(), frame.local(&wi), ray.time()),
+            pdf: Some(pdf),
+        })
+    }
+
+    fn bsdf_eval(&self, hit: &HitRecord, wi: &Vector3, wo: &Vector3) -> Color {
+        if self.distribution.is_smooth() {
+            return Color::ZERO;
+        }
+
+        let frame = Onb::new(hit.normal());
+        let (wi, wo) = (frame.to_local(wi), frame.to_local(wo));
+        let (reflection, transmission) = eval(&self.distribution, self.eta(hit), &wi, &wo);
+        Color::splat(reflection + transmission)
+    }
+
+    fn pdf(&self, hit: &HitRecord, wi: &Vector3, wo: &Vector3) -> f32 {
+        if self.distribution.is_smooth() {
+            return 0.0;
+        }
+
+        let frame = Onb::new(hit.normal());
+        let (wi, wo) = (frame.to_local(wi), frame.to_local(wo));
+        pdf(&self.distribution, self.eta(hit), &wi, &wo)
+    }
+}
+
+// Local-frame lobe functions, shared with the transmission lobe of
+// `Principled`. `eta` is the far side index over the near side one and `wo`
+// is expected above the surface.
+
+/// Picks reflection or refraction through a visible microfacet by its Fresnel term.
+pub(crate) fn sample(distribution: &Ggx, eta: f32, wo: &Vector3) -> Option<Vector3> {
+    let h = distribution.sample_visible_normal(wo, rand(), rand());
+    let fresnel = fresnel_dielectric(wo.dot(h), eta);
+
+    if rand() < fresnel {
+        Some(reflect(wo, &h)).filter(|wi| wi.z > 0.0)
+    } else {
+        refract(wo, &h, eta).filter(|wi| wi.z < 0.0)
+    }
+}
+
+/// Reflected and transmitted BSDF times `|cos|`, at most one is non-zero.
+pub(crate) fn eval(distribution: &Ggx, eta: f32, wi: &Vector3, wo: &Vector3) -> (f32, f32) {
+    if wo.z <= 0.0 || wi.z == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let g2 = distribution.g2(wo, wi);
+
+    if wi.z > 0.0 {
+        let h = (wi + wo).normalize();
+        let fresnel = fresnel_dielectric(wo.dot(h), eta);
+        return (fresnel * distribution.d(&h) * g2 / (4.0 * wo.z), 0.0);
+    }
+
+    let Some(h) = transmission_normal(wi, wo, eta) else {
+        return (0.0, 0.0);
+    };
+    let transmitted = 1.0 - fresnel_dielectric(wo.dot(h), eta);
+    let denom = wi.dot(h) + wo.dot(h) / eta;
+    let f_cos = transmitted * distribution.d(&h) * g2 * wi.dot(h).abs() * wo.dot(h)
+        / (wo.z * denom * denom);
+
+    (0.0, f_cos)
+}
+
+pub(crate) fn pdf(distribution: &Ggx, eta: f32, wi: &Vector3, wo: &Vector3) -> f32 {
+    if wo.z <= 0.0 || wi.z == 0.0 {
+        return 0.0;
+    }
+
+    if wi.z > 0.0 {
+        let h = (wi + wo).normalize();
+        let fresnel = fresnel_dielectric(wo.dot(h), eta);
+        return fresnel * distribution.visible_normal_pdf(wo, &h) / (4.0 * wo.dot(h));
+    }
+
+    let Some(h) = transmission_normal(wi, wo, eta) else {
+        return 0.0;
+    };
+    let fresnel = fresnel_dielectric(wo.dot(h), eta);
+    let denom = wi.dot(h) + wo.dot(h) / eta;
+    (1.0 - fresnel) * distribution.visible_normal_pdf(wo, &h) * wi.dot(h).abs() / (denom * denom)
+}
+
+/// Microfacet normal that refracts `wo` into `wi`, `None` if the pair can't
+/// be connected by transmission.
+fn transmission_normal(wi: &Vector3, wo: &Vector3, eta: f32) -> Option<Vector3> {
+    let h = (wi * eta + wo).normalize();
+    let h = if h.z < 0.0 { -h } else { h };
+    if !h.is_finite() || wi.dot(h) >= 0.0 || wo.dot(h) <= 0.0 {
+        return None;
+    }
+
+    Some(h)
+}
@@ -1,12 +1,17 @@
+pub mod aabb;
+pub mod distribution;
+pub mod onb;
 pub mod ray;
 
 use rand::Rng;
 use rand::SeedableRng;
 use rand_xoshiro::Xoshiro256Plus;
-use std::f32::EPSILON;
 use std::ops::Neg;
 
+pub use aabb::Aabb;
+pub use distribution::{Distribution1D, Distribution2D};
 use glam::Vec3A;
+pub use onb::Onb;
 pub use ray::Ray;
 
 pub type Vector3 = Vec3A;
@@ -22,23 +27,46 @@ pub fn degrees_to_radians(degrees: f32) -> f32 {
     degrees * std::f32::consts::PI / 180.0
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct Interval {
     min: f32,
     max: f32,
 }
 
-impl Interval {
-    pub fn default() -> Self {
+impl Default for Interval {
+    fn default() -> Self {
         Self {
-            min: std::f32::NEG_INFINITY,
-            max: std::f32::INFINITY,
+            min: f32::NEG_INFINITY,
+            max: f32::INFINITY,
         }
     }
+}
 
+impl Interval {
     pub fn new(min: f32, max: f32) -> Self {
         Self { min, max }
     }
 
+    /// Smallest interval enclosing both `a` and `b`.
+    pub fn union(a: &Interval, b: &Interval) -> Self {
+        Self {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
+    }
+
+    pub fn size(&self) -> f32 {
+        self.max - self.min
+    }
+
+    pub fn expand(&self, delta: f32) -> Self {
+        let padding = delta / 2.0;
+        Self {
+            min: self.min - padding,
+            max: self.max + padding,
+        }
+    }
+
     pub fn contains(&self, x: f32) -> bool {
         self.min <= x && x <= self.max
     }
@@ -68,16 +96,34 @@ impl Interval {
     }
 }
 
-pub static EMPTY: Interval = Interval {
-    min: std::f32::INFINITY,
-    max: std::f32::NEG_INFINITY,
+pub const EMPTY: Interval = Interval {
+    min: f32::INFINITY,
+    max: f32::NEG_INFINITY,
 };
 
-pub static UNIVERSE: Interval = Interval {
-    min: std::f32::NEG_INFINITY,
-    max: std::f32::INFINITY,
+pub const UNIVERSE: Interval = Interval {
+    min: f32::NEG_INFINITY,
+    max: f32::INFINITY,
 };
 
+/// Reseeds the calling thread's generator so that the following `rand*` calls
+/// produce a sequence that depends only on `seed`.
+pub fn seed_rng(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = Xoshiro256Plus::seed_from_u64(seed));
+}
+
+/// Derives a well-mixed seed for a single pixel sample, so the image does not
+/// depend on which thread rendered which pixel.
+pub fn sample_seed(seed: u64, pixel: u64, sample: u64) -> u64 {
+    let mut z = seed
+        ^ pixel.wrapping_mul(0x9e37_79b9_7f4a_7c15)
+        ^ sample.wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
 #[inline]
 pub fn rand() -> f32 {
     RNG.with(|rng| rng.borrow_mut().random::<f32>())
@@ -103,7 +149,7 @@ pub fn rand_unit_vec3() -> Vector3 {
     loop {
         let p = rand_rng_vec3(-1.0, 1.0);
         let len_sq = p.length_squared();
-        if EPSILON < len_sq && len_sq < 1.0 {
+        if f32::EPSILON < len_sq && len_sq < 1.0 {
             return p.normalize();
         }
     }
@@ -119,6 +165,17 @@ pub fn rand_hemnisphere_vec3(normal: &Vector3) -> Vector3 {
     }
 }
 
+/// Cosine-distributed direction around +Z, to be placed with an [`Onb`].
+pub fn rand_cosine_direction() -> Vector3 {
+    let r1 = rand();
+    let r2 = rand();
+
+    let phi = 2.0 * std::f32::consts::PI * r1;
+    let r = r2.sqrt();
+
+    Vector3::new(phi.cos() * r, phi.sin() * r, (1.0 - r2).sqrt())
+}
+
 pub trait Vector3Ext {
     fn near_zero(&self) -> bool;
 }
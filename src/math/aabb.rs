@@ -0,0 +1,144 @@
+use crate::math::{EMPTY, Interval, Point3, Ray, UNIVERSE};
+
+/// Axis-aligned bounding box stored as one interval per axis.
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    x: Interval,
+    y: Interval,
+    z: Interval,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl Aabb {
+    pub const EMPTY: Aabb = Aabb {
+        x: EMPTY,
+        y: EMPTY,
+        z: EMPTY,
+    };
+
+    /// Box of unbounded objects such as infinite planes.
+    pub const UNIVERSE: Aabb = Aabb {
+        x: UNIVERSE,
+        y: UNIVERSE,
+        z: UNIVERSE,
+    };
+
+    pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        Self { x, y, z }.pad_to_minimums()
+    }
+
+    /// Box spanned by two opposite corners, given in any order.
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        let min = a.min(b);
+        let max = a.max(b);
+
+        Self::new(
+            Interval::new(min.x, max.x),
+            Interval::new(min.y, max.y),
+            Interval::new(min.z, max.z),
+        )
+    }
+
+    pub fn union(a: &Aabb, b: &Aabb) -> Self {
+        Self {
+            x: Interval::union(&a.x, &b.x),
+            y: Interval::union(&a.y, &b.y),
+            z: Interval::union(&a.z, &b.z),
+        }
+    }
+
+    pub fn axis(&self, n: usize) -> &Interval {
+        match n {
+            1 => &self.y,
+            2 => &self.z,
+            _ => &self.x,
+        }
+    }
+
+    pub fn min(&self) -> Point3 {
+        Point3::new(self.x.min(), self.y.min(), self.z.min())
+    }
+
+    pub fn max(&self) -> Point3 {
+        Point3::new(self.x.max(), self.y.max(), self.z.max())
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        0.5 * (self.min() + self.max())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.min() > self.x.max() || self.y.min() > self.y.max() || self.z.min() > self.z.max()
+    }
+
+    /// Whether the box is finite, empty boxes count as bounded.
+    pub fn is_bounded(&self) -> bool {
+        self.is_empty() || (self.min().is_finite() && self.max().is_finite())
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let (dx, dy, dz) = (self.x.size(), self.y.size(), self.z.size());
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let (dx, dy, dz) = (self.x.size(), self.y.size(), self.z.size());
+        if dx > dy && dx > dz {
+            0
+        } else if dy > dz {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Slab test, returns whether the ray enters the box inside `ray_t`.
+    pub fn hit(&self, ray: &Ray, ray_t: &Interval) -> bool {
+        let orig = ray.orig();
+        let dir = ray.dir();
+        let mut t_min = ray_t.min();
+        let mut t_max = ray_t.max();
+
+        for axis in 0..3 {
+            let ax = self.axis(axis);
+            let inv_d = 1.0 / dir[axis];
+
+            let t0 = (ax.min() - orig[axis]) * inv_d;
+            let t1 = (ax.max() - orig[axis]) * inv_d;
+            let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+
+            if t_max <= t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Flat primitives get a thin but non-zero extent so the slab test stays robust.
+    fn pad_to_minimums(mut self) -> Self {
+        let delta = 0.0001;
+        if self.x.size() < delta {
+            self.x = self.x.expand(delta);
+        }
+        if self.y.size() < delta {
+            self.y = self.y.expand(delta);
+        }
+        if self.z.size() < delta {
+            self.z = self.z.expand(delta);
+        }
+        self
+    }
+}
@@ -0,0 +1,115 @@
+use glam::Vec2;
+
+/// Piecewise-constant distribution over [0, 1) proportional to `func`.
+///
+/// A function that is zero everywhere is sampled uniformly.
+pub struct Distribution1D {
+    func: Vec<f32>,
+    cdf: Vec<f32>,
+    integral: f32,
+}
+
+impl Distribution1D {
+    pub fn new(func: Vec<f32>) -> Self {
+        let n = func.len();
+        let mut cdf = Vec::with_capacity(n + 1);
+        cdf.push(0.0);
+        for (i, f) in func.iter().enumerate() {
+            cdf.push(cdf[i] + f.abs() / n as f32);
+        }
+
+        let integral = cdf[n];
+        for (i, c) in cdf.iter_mut().enumerate().skip(1) {
+            *c = if integral > 0.0 {
+                *c / integral
+            } else {
+                i as f32 / n as f32
+            };
+        }
+
+        Self {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    /// Average of the function over [0, 1).
+    pub fn integral(&self) -> f32 {
+        self.integral
+    }
+
+    pub fn len(&self) -> usize {
+        self.func.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.func.is_empty()
+    }
+
+    /// Density of the segment `index` contains.
+    pub fn pdf(&self, index: usize) -> f32 {
+        if self.integral > 0.0 {
+            self.func[index].abs() / self.integral
+        } else {
+            1.0
+        }
+    }
+
+    /// Maps a uniform `u` to a point in [0, 1), returning it with its density
+    /// and segment index.
+    pub fn sample(&self, u: f32) -> (f32, f32, usize) {
+        let n = self.len();
+        let index = self
+            .cdf
+            .partition_point(|&c| c <= u)
+            .saturating_sub(1)
+            .min(n - 1);
+
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let offset = if width > 0.0 {
+            ((u - self.cdf[index]) / width).clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+
+        let x = ((index as f32 + offset) / n as f32).min(1.0 - f32::EPSILON);
+        (x, self.pdf(index), index)
+    }
+}
+
+/// Piecewise-constant distribution over [0, 1)² proportional to a grid of
+/// `width` × `height` values stored row by row.
+pub struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new(func: &[f32], width: usize, height: usize) -> Self {
+        let rows: Vec<Distribution1D> = func
+            .chunks_exact(width)
+            .take(height)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+        let marginal = Distribution1D::new(rows.iter().map(|r| r.integral()).collect());
+
+        Self { rows, marginal }
+    }
+
+    /// Point sampled from two uniform numbers, with its density.
+    pub fn sample(&self, u1: f32, u2: f32) -> (Vec2, f32) {
+        let (y, pdf_y, row) = self.marginal.sample(u2);
+        let (x, pdf_x, _) = self.rows[row].sample(u1);
+
+        (Vec2::new(x, y), pdf_x * pdf_y)
+    }
+
+    pub fn pdf(&self, p: Vec2) -> f32 {
+        let row = ((p.y * self.marginal.len() as f32) as usize).min(self.marginal.len() - 1);
+        let columns = &self.rows[row];
+        let column = ((p.x * columns.len() as f32) as usize).min(columns.len() - 1);
+
+        self.marginal.pdf(row) * columns.pdf(column)
+    }
+}
@@ -0,0 +1,46 @@
+use super::Vector3;
+
+/// Orthonormal basis with `w` along a given direction.
+#[derive(Debug, Clone, Copy)]
+pub struct Onb {
+    u: Vector3,
+    v: Vector3,
+    w: Vector3,
+}
+
+impl Onb {
+    pub fn new(n: &Vector3) -> Self {
+        let w = n.normalize();
+        let a = if w.x.abs() > 0.9 {
+            Vector3::Y
+        } else {
+            Vector3::X
+        };
+        let v = w.cross(a).normalize();
+        let u = w.cross(v);
+
+        Self { u, v, w }
+    }
+
+    pub fn u(&self) -> &Vector3 {
+        &self.u
+    }
+
+    pub fn v(&self) -> &Vector3 {
+        &self.v
+    }
+
+    pub fn w(&self) -> &Vector3 {
+        &self.w
+    }
+
+    /// Transforms `a`, given in basis coordinates, to world space.
+    pub fn local(&self, a: &Vector3) -> Vector3 {
+        a.x * self.u + a.y * self.v + a.z * self.w
+    }
+
+    /// Transforms world space `a` to basis coordinates, inverse of [`Onb::local`].
+    pub fn to_local(&self, a: &Vector3) -> Vector3 {
+        Vector3::new(a.dot(self.u), a.dot(self.v), a.dot(self.w))
+    }
+}
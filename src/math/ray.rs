@@ -4,11 +4,18 @@ use crate::math::{Point3, Vector3};
 pub struct Ray {
     orig: Point3,
     dir: Vector3,
+    time: f32,
 }
 
 impl Ray {
     pub fn new(orig: Point3, dir: Vector3) -> Self {
-        Self { orig, dir }
+        Self::with_time(orig, dir, 0.0)
+    }
+
+    /// Ray sent at `time` during the exposure, moving objects are hit where
+    /// they are at that instant.
+    pub fn with_time(orig: Point3, dir: Vector3, time: f32) -> Self {
+        Self { orig, dir, time }
     }
 
     pub fn orig(&self) -> &Point3 {
@@ -19,6 +26,10 @@ impl Ray {
         &self.dir
     }
 
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
     pub fn at(&self, t: f32) -> Point3 {
         self.orig + self.dir * t
     }
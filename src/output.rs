@@ -0,0 +1,248 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use exr::prelude::{Encoding, Image, Layer, LayerAttributes, SpecificChannels, WritableImage, f16};
+use image::codecs::hdr::HdrEncoder;
+use image::{ImageError, ImageFormat, Rgb, RgbImage};
+
+use crate::camera::Camera;
+use crate::color::ColorSpace;
+use crate::hittable::{Hittable, HittableList};
+use crate::tonemap::DisplayTransform;
+use crate::{Buffer, FrameBuffer};
+
+/// Sample type of the EXR channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExrPrecision {
+    Half,
+    Float,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// 8-bit display-referred image written through the `image` crate.
+    Ldr(ImageFormat),
+    /// Linear OpenEXR with R, G, B and A channels.
+    Exr(ExrPrecision),
+    /// Linear Radiance RGBE.
+    Hdr,
+    /// Linear portable float map.
+    Pfm,
+}
+
+impl OutputFormat {
+    /// Accepts `exr` (float), `exr-half`, `exr-float`, `hdr`, `pfm` and any
+    /// extension known to the `image` crate.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "exr" | "exr-float" => Some(OutputFormat::Exr(ExrPrecision::Float)),
+            "exr-half" => Some(OutputFormat::Exr(ExrPrecision::Half)),
+            "hdr" => Some(OutputFormat::Hdr),
+            "pfm" => Some(OutputFormat::Pfm),
+            other => ImageFormat::from_extension(other).map(OutputFormat::Ldr),
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(OutputFormat::from_extension)
+    }
+}
+
+#[derive(Debug)]
+pub enum OutputError {
+    Io(std::io::Error),
+    Image(ImageError),
+    Exr(exr::error::Error),
+}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputError::Io(err) => write!(f, "{}", err),
+            OutputError::Image(err) => write!(f, "{}", err),
+            OutputError::Exr(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for OutputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OutputError::Io(err) => Some(err),
+            OutputError::Image(err) => Some(err),
+            OutputError::Exr(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for OutputError {
+    fn from(err: std::io::Error) -> Self {
+        OutputError::Io(err)
+    }
+}
+
+impl From<ImageError> for OutputError {
+    fn from(err: ImageError) -> Self {
+        OutputError::Image(err)
+    }
+}
+
+impl From<exr::error::Error> for OutputError {
+    fn from(err: exr::error::Error) -> Self {
+        OutputError::Exr(err)
+    }
+}
+
+/// Unpacks the ARGB display buffer into an 8-bit RGB image.
+pub fn buffer_to_image(bf: &Buffer) -> RgbImage {
+    let (width, height) = (bf.width() as u32, bf.height() as u32);
+    let mut img = RgbImage::new(width, height);
+
+    for (pixel, &value) in img.pixels_mut().zip(bf.buffer()) {
+        let r = ((value >> 16) & 0xFF) as u8;
+        let g = ((value >> 8) & 0xFF) as u8;
+        let b = (value & 0xFF) as u8;
+
+        *pixel = Rgb([r, g, b]);
+    }
+
+    img
+}
+
+/// Writes `fb` in `format`. 8-bit formats go through the full `display`
+/// transform, float formats keep linear radiance in sRGB primaries.
+pub fn save(
+    fb: &FrameBuffer,
+    path: &Path,
+    format: OutputFormat,
+    display: &DisplayTransform,
+) -> Result<(), OutputError> {
+    let converted = match format {
+        OutputFormat::Ldr(_) => None,
+        _ => to_srgb_primaries(fb, display.working_space),
+    };
+    let linear = converted.as_ref().unwrap_or(fb);
+
+    match format {
+        OutputFormat::Ldr(format) => save_image(fb, path, format, display),
+        OutputFormat::Exr(precision) => save_exr(linear, path, precision),
+        OutputFormat::Hdr => save_hdr(linear, path),
+        OutputFormat::Pfm => save_pfm(linear, path),
+    }
+}
+
+/// Converts `fb` out of the working space, `None` when it is already sRGB.
+fn to_srgb_primaries(fb: &FrameBuffer, space: ColorSpace) -> Option<FrameBuffer> {
+    if space == ColorSpace::LinearSrgb {
+        return None;
+    }
+
+    let mut converted = FrameBuffer::new(fb.width(), fb.height());
+    for y in 0..fb.height() {
+        for x in 0..fb.width() {
+            converted.set_color(x, y, &space.to_srgb(fb.color(x, y)));
+        }
+    }
+    Some(converted)
+}
+
+/// Writes `fb` as an 8-bit display-referred image.
+pub fn save_image(
+    fb: &FrameBuffer,
+    path: &Path,
+    format: ImageFormat,
+    display: &DisplayTransform,
+) -> Result<(), OutputError> {
+    let mut bf = Buffer::new(fb.width(), fb.height());
+    fb.to_buffer(&mut bf, display);
+
+    buffer_to_image(&bf).save_with_format(path, format)?;
+    Ok(())
+}
+
+/// Writes the linear radiance as a ZIP-compressed single-layer EXR.
+pub fn save_exr(fb: &FrameBuffer, path: &Path, precision: ExrPrecision) -> Result<(), OutputError> {
+    let size = (fb.width(), fb.height());
+    let pixel = |x: usize, y: usize| fb.pixels()[y * fb.width() + x];
+    let attributes = LayerAttributes::named("beauty");
+
+    match precision {
+        ExrPrecision::Float => {
+            let channels = SpecificChannels::rgba(|pos: exr::math::Vec2<usize>| {
+                let p = pixel(pos.x(), pos.y());
+                (p.x, p.y, p.z, p.w)
+            });
+            let layer = Layer::new(size, attributes, Encoding::SMALL_LOSSLESS, channels);
+            Image::from_layer(layer).write().to_file(path)?;
+        }
+        ExrPrecision::Half => {
+            let channels = SpecificChannels::rgba(|pos: exr::math::Vec2<usize>| {
+                let p = pixel(pos.x(), pos.y());
+                (
+                    f16::from_f32(p.x),
+                    f16::from_f32(p.y),
+                    f16::from_f32(p.z),
+                    f16::from_f32(p.w),
+                )
+            });
+            let layer = Layer::new(size, attributes, Encoding::SMALL_LOSSLESS, channels);
+            Image::from_layer(layer).write().to_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the linear radiance as a Radiance RGBE `.hdr` file.
+pub fn save_hdr(fb: &FrameBuffer, path: &Path) -> Result<(), OutputError> {
+    let pixels: Vec<Rgb<f32>> = fb
+        .pixels()
+        .iter()
+        .map(|p| Rgb([p.x.max(0.0), p.y.max(0.0), p.z.max(0.0)]))
+        .collect();
+
+    let writer = BufWriter::new(File::create(path)?);
+    HdrEncoder::new(writer).encode(&pixels, fb.width(), fb.height())?;
+    Ok(())
+}
+
+/// Writes the linear radiance as a little-endian RGB portable float map.
+///
+/// PFM stores rows bottom to top.
+pub fn save_pfm(fb: &FrameBuffer, path: &Path) -> Result<(), OutputError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write!(writer, "PF\n{} {}\n-1.0\n", fb.width(), fb.height())?;
+
+    for row in fb.pixels().chunks(fb.width()).rev() {
+        for p in row {
+            for channel in [p.x, p.y, p.z] {
+                writer.write_all(&channel.to_le_bytes())?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Renders `world` and writes the image to `path`, without any window.
+pub fn render_to_file(
+    camera: &Camera,
+    world: &dyn Hittable,
+    lights: &HittableList,
+    path: &Path,
+    format: OutputFormat,
+    display: &DisplayTransform,
+) -> Result<(), OutputError> {
+    let mut fb = FrameBuffer::new(
+        camera.image_width() as usize,
+        camera.image_height() as usize,
+    );
+    camera.render(&mut fb, world, lights);
+
+    save(&fb, path, format, display)
+}
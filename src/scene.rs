@@ -0,0 +1,761 @@
+pub mod description;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use glam::{Affine3A, Quat, Vec2, Vec3};
+use toml::Spanned;
+
+use crate::background::{
+    Background, EnvironmentMap, GradientBackground, SkyBackground, SkyConfig, SolidBackground,
+};
+use crate::camera::{Camera, CameraConfig};
+use crate::color::{Color, ColorSpace};
+use crate::hittable::sphere::Sphere;
+use crate::hittable::{
+    ConstantMedium, Disk, Hittable, HittableList, Instance, Keyframe, Plane, Quad, Triangle, cuboid,
+};
+use crate::lights::{DirectionalLight, Light, PointLight, SpotLight};
+use crate::loaders::{ObjError, load_obj};
+use crate::materials::{
+    Conductor, ConductorConfig, Dielectric, DielectricConfig, DiffuseLight, DiffuseLightConfig,
+    Isotropic, IsotropicConfig, Lambertian, LambertianConfig, Material, Metal, MetalConfig,
+    Principled, PrincipledConfig, RoughDielectric, RoughDielectricConfig, Scalar,
+};
+use crate::math::{Point3, Vector3};
+use crate::textures::{
+    CheckerTexture, ImageTexture, NoiseTexture, SolidColor, Texture, UvCheckerTexture,
+};
+
+pub use description::SceneDescription;
+use description::{
+    BackgroundDescription, LightDescription, MaterialDescription, ObjectDescription,
+    RenderSettings, ScalarRef, TextureDescription, TextureRef,
+};
+
+const DEFAULT_ASPECT_RATIO: f32 = 16.0 / 9.0;
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Malformed TOML or a field with the wrong name or type.
+    Syntax {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Well-formed file describing an impossible scene, e.g. an unknown material.
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Mesh(ObjError),
+    Texture {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            SceneError::Syntax {
+                path,
+                line,
+                column,
+                message,
+            }
+            | SceneError::Invalid {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+            SceneError::Mesh(err) => write!(f, "{}", err),
+            SceneError::Texture { path, source } => {
+                write!(f, "{}: failed to load texture: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Io { source, .. } => Some(source),
+            SceneError::Mesh(err) => Some(err),
+            SceneError::Texture { source, .. } => Some(source),
+            SceneError::Syntax { .. } | SceneError::Invalid { .. } => None,
+        }
+    }
+}
+
+impl From<ObjError> for SceneError {
+    fn from(err: ObjError) -> Self {
+        SceneError::Mesh(err)
+    }
+}
+
+/// Camera settings and world built from a scene file.
+pub struct Scene {
+    pub config: CameraConfig,
+    pub world: HittableList,
+    /// Emitters sampled directly, also present in `world`.
+    pub lights: HittableList,
+    /// Working space of every color in `world`.
+    pub color_space: ColorSpace,
+}
+
+impl Scene {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|source| SceneError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        Scene::parse(&source, path)
+    }
+
+    /// Parses scene source; `path` is used in error messages and to resolve
+    /// relative texture and mesh paths.
+    pub fn parse(source: &str, path: &Path) -> Result<Self, SceneError> {
+        let description = SceneDescription::parse(source).map_err(|err| {
+            let (line, column) = line_column(source, err.span);
+            SceneError::Syntax {
+                path: path.to_path_buf(),
+                line,
+                column,
+                message: err.message,
+            }
+        })?;
+
+        SceneBuilder {
+            source,
+            path,
+            dir: path.parent().unwrap_or(Path::new("")),
+            space: description.render.color_space,
+            textures: HashMap::new(),
+            materials: HashMap::new(),
+            emitters: HashSet::new(),
+            phase_functions: HashSet::new(),
+        }
+        .build(description)
+    }
+
+    pub fn camera(&self) -> Camera {
+        Camera::new(&self.config)
+    }
+}
+
+struct SceneBuilder<'a> {
+    source: &'a str,
+    path: &'a Path,
+    dir: &'a Path,
+    space: ColorSpace,
+    textures: HashMap<String, Arc<dyn Texture>>,
+    materials: HashMap<String, Arc<dyn Material>>,
+    /// Names of materials that emit light.
+    emitters: HashSet<String>,
+    /// Names of isotropic materials, the only ones a medium can scatter with.
+    phase_functions: HashSet<String>,
+}
+
+impl SceneBuilder<'_> {
+    fn build(mut self, description: SceneDescription) -> Result<Scene, SceneError> {
+        for name in description.textures.keys() {
+            self.texture(name, &description.textures, &mut HashSet::new())?;
+        }
+
+        for (name, material) in &description.materials {
+            let built = self.material(name, material, &description.textures)?;
+            self.materials.insert(name.clone(), built);
+        }
+
+        let mut world = HittableList::new();
+        let mut lights = HittableList::new();
+        for (index, object) in description.objects.iter().enumerate() {
+            let built = self.object(object.get_ref(), object.span(), index, &mut lights)?;
+            world.add(built);
+        }
+
+        let mut delta_lights = Vec::with_capacity(description.lights.len());
+        for (index, light) in description.lights.iter().enumerate() {
+            delta_lights.push(self.light(light.get_ref(), light.span(), index)?);
+        }
+
+        let render = &description.render;
+        let camera = &description.camera;
+        let background: Arc<dyn Background> = match description.background {
+            BackgroundDescription::Solid { color } => {
+                Arc::new(SolidBackground::new(self.color(color)))
+            }
+            BackgroundDescription::Gradient { horizon, zenith } => Arc::new(
+                GradientBackground::new(self.color(horizon), self.color(zenith)),
+            ),
+            BackgroundDescription::Environment {
+                path,
+                rotation,
+                intensity,
+            } => {
+                let path = self.dir.join(path);
+                let image = ImageTexture::load(&path, self.space)
+                    .map_err(|source| SceneError::Texture { path, source })?;
+                Arc::new(EnvironmentMap::new(image, rotation, intensity))
+            }
+            BackgroundDescription::Sky(sky) => {
+                let config = SkyConfig {
+                    sun_elevation: sky.sun_elevation,
+                    sun_azimuth: sky.sun_azimuth,
+                    turbidity: sky.turbidity,
+                    sun_size: sky.sun_size,
+                    sun_intensity: sky.sun_intensity,
+                };
+                Arc::new(SkyBackground::new(&config, self.space))
+            }
+        };
+
+        let (image_height, aspect_ratio) = self.image_size(render)?;
+        if *render.tile_size.get_ref() == 0 {
+            return Err(self.invalid(
+                render.tile_size.span(),
+                "render.tile_size: must be at least 1".to_string(),
+            ));
+        }
+        let config = CameraConfig {
+            image_width: render.width.get(),
+            image_height,
+            aspect_ratio,
+            samples_per_pixel: render.samples_per_pixel.get(),
+            max_ray_depth: render.max_depth,
+            light_intensity: render.light_intensity,
+            background,
+            delta_lights,
+            fov: camera.fov,
+            look_from: vec3(camera.look_from),
+            look_at: vec3(camera.look_at),
+            defocus_angle: camera.defocus_angle,
+            focus_dist: camera.focus_dist,
+            shutter_open: camera.shutter_open,
+            shutter_close: camera.shutter_close,
+            threads: render.threads,
+            tile_size: *render.tile_size.get_ref(),
+            tile_order: render.tile_order,
+            seed: render.seed,
+        };
+
+        Ok(Scene {
+            config,
+            world,
+            lights,
+            color_space: self.space,
+        })
+    }
+
+    /// Image height and aspect ratio, whichever of the two is missing derived
+    /// from the other.
+    fn image_size(&self, render: &RenderSettings) -> Result<(u32, f32), SceneError> {
+        let width = render.width.get() as f32;
+        if let Some(aspect_ratio) = &render.aspect_ratio
+            && *aspect_ratio.get_ref() <= 0.0
+        {
+            return Err(self.invalid(
+                aspect_ratio.span(),
+                "render.aspect_ratio: must be positive".to_string(),
+            ));
+        }
+
+        match (render.height, &render.aspect_ratio) {
+            (Some(height), Some(aspect_ratio))
+                // A height derived from the aspect ratio is rounded to whole pixels.
+                if (width / aspect_ratio.get_ref() - height.get() as f32).abs() > 1.0 =>
+            {
+                Err(self.invalid(
+                    aspect_ratio.span(),
+                    format!(
+                        "render.aspect_ratio: {} conflicts with width {} and height {}",
+                        aspect_ratio.get_ref(),
+                        render.width,
+                        height
+                    ),
+                ))
+            }
+            (Some(height), _) => Ok((height.get(), width / height.get() as f32)),
+            (None, aspect_ratio) => {
+                let aspect_ratio = aspect_ratio
+                    .as_ref()
+                    .map_or(DEFAULT_ASPECT_RATIO, |a| *a.get_ref());
+                Ok((((width / aspect_ratio) as u32).max(1), aspect_ratio))
+            }
+        }
+    }
+
+    /// Converts an sRGB color from the file into the working space.
+    fn color(&self, c: [f32; 3]) -> Color {
+        self.space.from_srgb(vec3(c))
+    }
+
+    fn invalid(&self, span: Range<usize>, message: String) -> SceneError {
+        let (line, column) = line_column(self.source, span);
+        SceneError::Invalid {
+            path: self.path.to_path_buf(),
+            line,
+            column,
+            message,
+        }
+    }
+
+    /// Builds the named texture, building the textures it references first.
+    fn texture(
+        &mut self,
+        name: &str,
+        all: &BTreeMap<String, Spanned<TextureDescription>>,
+        visiting: &mut HashSet<String>,
+    ) -> Result<Arc<dyn Texture>, SceneError> {
+        if let Some(texture) = self.textures.get(name) {
+            return Ok(Arc::clone(texture));
+        }
+
+        let Some(description) = all.get(name) else {
+            return Err(self.invalid(0..0, format!("unknown texture `{}`", name)));
+        };
+        let span = description.span();
+        if !visiting.insert(name.to_string()) {
+            return Err(self.invalid(span, format!("texture `{}` references itself", name)));
+        }
+
+        let field = |f: &str| format!("textures.{}.{}", name, f);
+
+        let texture: Arc<dyn Texture> = match description.get_ref() {
+            TextureDescription::Solid { color } => Arc::new(SolidColor::new(self.color(*color))),
+            TextureDescription::Checker { scale, even, odd } => {
+                let even = self.texture_ref(even, all, visiting, &span, field("even"))?;
+                let odd = self.texture_ref(odd, all, visiting, &span, field("odd"))?;
+                Arc::new(CheckerTexture::new(*scale, even, odd))
+            }
+            TextureDescription::UvChecker {
+                columns,
+                rows,
+                even,
+                odd,
+            } => {
+                let even = self.texture_ref(even, all, visiting, &span, field("even"))?;
+                let odd = self.texture_ref(odd, all, visiting, &span, field("odd"))?;
+                Arc::new(UvCheckerTexture::new(*columns, *rows, even, odd))
+            }
+            TextureDescription::Image { path } => {
+                let path = self.dir.join(path);
+                let texture = ImageTexture::load(&path, self.space)
+                    .map_err(|source| SceneError::Texture { path, source })?;
+                Arc::new(texture)
+            }
+            TextureDescription::Noise { scale } => Arc::new(NoiseTexture::new(*scale)),
+        };
+
+        visiting.remove(name);
+        self.textures.insert(name.to_string(), Arc::clone(&texture));
+        Ok(texture)
+    }
+
+    fn texture_ref(
+        &mut self,
+        r: &TextureRef,
+        all: &BTreeMap<String, Spanned<TextureDescription>>,
+        visiting: &mut HashSet<String>,
+        span: &Range<usize>,
+        field: String,
+    ) -> Result<Arc<dyn Texture>, SceneError> {
+        match r {
+            TextureRef::Color(c) => Ok(Arc::new(SolidColor::new(self.color(*c)))),
+            TextureRef::Named(n) if all.contains_key(n) => self.texture(n, all, visiting),
+            TextureRef::Named(n) => {
+                Err(self.invalid(span.clone(), format!("{}: unknown texture `{}`", field, n)))
+            }
+        }
+    }
+
+    fn scalar_ref(
+        &mut self,
+        r: &ScalarRef,
+        all: &BTreeMap<String, Spanned<TextureDescription>>,
+        visiting: &mut HashSet<String>,
+        span: &Range<usize>,
+        field: String,
+    ) -> Result<Scalar, SceneError> {
+        match r {
+            ScalarRef::Value(value) => Ok(Scalar::Constant(*value)),
+            ScalarRef::Named(n) => {
+                let texture =
+                    self.texture_ref(&TextureRef::Named(n.clone()), all, visiting, span, field)?;
+                Ok(Scalar::Texture(texture))
+            }
+        }
+    }
+
+    fn material(
+        &mut self,
+        name: &str,
+        description: &Spanned<MaterialDescription>,
+        textures: &BTreeMap<String, Spanned<TextureDescription>>,
+    ) -> Result<Arc<dyn Material>, SceneError> {
+        let span = description.span();
+        let field = |f: &str| format!("materials.{}.{}", name, f);
+        let visiting = &mut HashSet::new();
+
+        let material: Arc<dyn Material> = match description.get_ref() {
+            MaterialDescription::Lambertian { albedo } => {
+                let albedo =
+                    self.texture_ref(albedo, textures, visiting, &span, field("albedo"))?;
+                Arc::new(Lambertian::new(LambertianConfig { albedo }))
+            }
+            MaterialDescription::Metal { albedo, fuzz } => {
+                let albedo =
+                    self.texture_ref(albedo, textures, visiting, &span, field("albedo"))?;
+                Arc::new(Metal::new(MetalConfig {
+                    albedo,
+                    fuzz: *fuzz,
+                }))
+            }
+            MaterialDescription::Dielectric { refraction_index } => {
+                Arc::new(Dielectric::new(DielectricConfig {
+                    refraction_index: *refraction_index,
+                }))
+            }
+            MaterialDescription::Conductor {
+                preset,
+                eta,
+                k,
+                roughness,
+            } => {
+                let config = match (preset, eta, k) {
+                    (Some(preset), None, None) => preset.config(*roughness),
+                    (None, Some(eta), Some(k)) => ConductorConfig {
+                        eta: vec3(*eta),
+                        k: vec3(*k),
+                        roughness: *roughness,
+                    },
+                    _ => {
+                        return Err(self.invalid(
+                            span,
+                            format!("materials.{}: needs either `preset` or `eta` and `k`", name),
+                        ));
+                    }
+                };
+                Arc::new(Conductor::new(config))
+            }
+            MaterialDescription::RoughDielectric {
+                refraction_index,
+                roughness,
+            } => Arc::new(RoughDielectric::new(RoughDielectricConfig {
+                refraction_index: *refraction_index,
+                roughness: *roughness,
+            })),
+            MaterialDescription::Principled(p) => {
+                let base_color = self.texture_ref(
+                    &p.base_color,
+                    textures,
+                    visiting,
+                    &span,
+                    field("base_color"),
+                )?;
+                let mut scalar = |r: &ScalarRef, f: &str| {
+                    self.scalar_ref(r, textures, visiting, &span, field(f))
+                };
+
+                Arc::new(Principled::new(PrincipledConfig {
+                    base_color,
+                    metallic: scalar(&p.metallic, "metallic")?,
+                    roughness: scalar(&p.roughness, "roughness")?,
+                    specular: scalar(&p.specular, "specular")?,
+                    sheen: scalar(&p.sheen, "sheen")?,
+                    clearcoat: scalar(&p.clearcoat, "clearcoat")?,
+                    clearcoat_roughness: scalar(&p.clearcoat_roughness, "clearcoat_roughness")?,
+                    transmission: scalar(&p.transmission, "transmission")?,
+                    ior: scalar(&p.ior, "ior")?,
+                }))
+            }
+            MaterialDescription::Isotropic { albedo } => {
+                let albedo =
+                    self.texture_ref(albedo, textures, visiting, &span, field("albedo"))?;
+                self.phase_functions.insert(name.to_string());
+                Arc::new(Isotropic::new(IsotropicConfig { albedo }))
+            }
+            MaterialDescription::DiffuseLight { emit } => {
+                let emit = self.texture_ref(emit, textures, visiting, &span, field("emit"))?;
+                self.emitters.insert(name.to_string());
+                Arc::new(DiffuseLight::new(DiffuseLightConfig { emit }))
+            }
+        };
+
+        Ok(material)
+    }
+
+    fn lookup_material(
+        &self,
+        name: &str,
+        span: Range<usize>,
+        index: usize,
+    ) -> Result<Arc<dyn Material>, SceneError> {
+        self.materials.get(name).cloned().ok_or_else(|| {
+            self.invalid(
+                span,
+                format!("objects[{}].material: unknown material `{}`", index, name),
+            )
+        })
+    }
+
+    /// Builds object `index`, adding spheres, triangles, quads, disks and boxes
+    /// with an emissive material to `lights` as well.
+    fn object(
+        &self,
+        description: &ObjectDescription,
+        span: Range<usize>,
+        index: usize,
+        lights: &mut HittableList,
+    ) -> Result<Box<dyn Hittable>, SceneError> {
+        let mat = self.lookup_material(description.material(), span.clone(), index)?;
+        let emissive = self.emitters.contains(description.material());
+
+        let object: Box<dyn Hittable> = match description {
+            ObjectDescription::Sphere {
+                center,
+                radius,
+                end_center,
+                ..
+            } => {
+                let sphere = match end_center {
+                    Some(end) => Sphere::moving(vec3(*center), vec3(*end), *radius, mat),
+                    None => Sphere::new(vec3(*center), *radius, mat),
+                };
+                if emissive && !sphere.is_moving() {
+                    lights.add(Box::new(sphere.clone()));
+                }
+                Box::new(sphere)
+            }
+            ObjectDescription::Triangle {
+                vertices,
+                normals,
+                uvs,
+                ..
+            } => {
+                let mut triangle = Triangle::new(vertices.map(vec3), mat);
+                if let Some(normals) = normals {
+                    triangle = triangle.with_normals(normals.map(vec3));
+                }
+                if let Some(uvs) = uvs {
+                    triangle = triangle.with_uvs(uvs.map(Vec2::from_array));
+                }
+                if emissive {
+                    lights.add(Box::new(triangle.clone()));
+                }
+                Box::new(triangle)
+            }
+            ObjectDescription::Quad { q, u, v, .. } => {
+                let quad = Quad::new(vec3(*q), vec3(*u), vec3(*v), mat);
+                if emissive {
+                    lights.add(Box::new(quad.clone()));
+                }
+                Box::new(quad)
+            }
+            ObjectDescription::Disk {
+                center,
+                normal,
+                radius,
+                ..
+            } => {
+                let disk = Disk::new(vec3(*center), vec3(*normal), *radius, mat);
+                if emissive {
+                    lights.add(Box::new(disk.clone()));
+                }
+                Box::new(disk)
+            }
+            ObjectDescription::Plane { point, normal, .. } => {
+                Box::new(Plane::new(vec3(*point), vec3(*normal), mat))
+            }
+            ObjectDescription::Cuboid { min, max, .. } => {
+                let (min, max) = (vec3(*min), vec3(*max));
+                if emissive {
+                    lights.add(Box::new(cuboid(min, max, Arc::clone(&mat))));
+                }
+                Box::new(cuboid(min, max, mat))
+            }
+            ObjectDescription::Mesh { path, .. } => {
+                Box::new(load_obj(self.dir.join(path), mat, self.space)?)
+            }
+            ObjectDescription::ConstantMedium { boundary, density } => {
+                if *density <= 0.0 {
+                    return Err(self.invalid(
+                        span,
+                        format!("objects[{}].density: must be positive", index),
+                    ));
+                }
+                if !self.phase_functions.contains(description.material()) {
+                    return Err(self.invalid(
+                        span,
+                        format!(
+                            "objects[{}].boundary.material: `{}` is not an isotropic material",
+                            index,
+                            description.material()
+                        ),
+                    ));
+                }
+                // The boundary only delimits the volume, it is never a light.
+                let boundary = self.object(boundary, span, index, &mut HittableList::new())?;
+                Box::new(ConstantMedium::new(boundary, *density, mat))
+            }
+            ObjectDescription::Instance {
+                object,
+                translate,
+                rotate,
+                scale,
+                keyframes,
+            } => {
+                let field = format!("objects[{}]", index);
+                let (scale, rotation, translation) =
+                    self.pose(*translate, *rotate, *scale, &span, &field)?;
+                let transform =
+                    Affine3A::from_scale_rotation_translation(scale, rotation, translation);
+
+                let mut object_lights = HittableList::new();
+                let object: Arc<dyn Hittable> = self
+                    .object(object, span.clone(), index, &mut object_lights)?
+                    .into();
+
+                if keyframes.is_empty() {
+                    let instance = Instance::new(object, transform);
+                    if !object_lights.is_empty() {
+                        lights.add(Box::new(instance.clone()));
+                    }
+                    Box::new(instance)
+                } else {
+                    let object = if transform == Affine3A::IDENTITY {
+                        object
+                    } else {
+                        Arc::new(Instance::new(object, transform))
+                    };
+
+                    let mut frames = Vec::with_capacity(keyframes.len());
+                    for (i, keyframe) in keyframes.iter().enumerate() {
+                        let field = format!("objects[{}].keyframes[{}]", index, i);
+                        let (scale, rotation, translation) = self.pose(
+                            keyframe.translate,
+                            keyframe.rotate,
+                            keyframe.scale,
+                            &span,
+                            &field,
+                        )?;
+                        frames.push(Keyframe {
+                            time: keyframe.time,
+                            scale,
+                            rotation,
+                            translation,
+                        });
+                    }
+
+                    // Moving objects aren't sampled as lights.
+                    Box::new(Instance::keyframed(object, frames))
+                }
+            }
+        };
+
+        Ok(object)
+    }
+
+    fn light(
+        &self,
+        description: &LightDescription,
+        span: Range<usize>,
+        index: usize,
+    ) -> Result<Arc<dyn Light>, SceneError> {
+        let light: Arc<dyn Light> = match description {
+            LightDescription::Point {
+                position,
+                color,
+                intensity,
+            } => Arc::new(PointLight::new(
+                vec3(*position),
+                self.color(*color) * *intensity,
+            )),
+            LightDescription::Spot {
+                position,
+                direction,
+                angle,
+                falloff,
+                color,
+                intensity,
+            } => {
+                if !(*angle > 0.0 && *angle <= 180.0) {
+                    return Err(self.invalid(
+                        span,
+                        format!("lights[{}].angle: must be in (0, 180] degrees", index),
+                    ));
+                }
+                Arc::new(SpotLight::new(
+                    vec3(*position),
+                    vec3(*direction),
+                    self.color(*color) * *intensity,
+                    *angle,
+                    *falloff,
+                ))
+            }
+            LightDescription::Directional {
+                direction,
+                color,
+                intensity,
+            } => Arc::new(DirectionalLight::new(
+                vec3(*direction),
+                self.color(*color) * *intensity,
+            )),
+        };
+
+        Ok(light)
+    }
+
+    /// Scale, rotation and translation of an instance or keyframe, with
+    /// `rotate` in degrees about the x, y and z axes in that order.
+    fn pose(
+        &self,
+        translate: [f32; 3],
+        rotate: [f32; 3],
+        scale: [f32; 3],
+        span: &Range<usize>,
+        field: &str,
+    ) -> Result<(Vec3, Quat, Vec3), SceneError> {
+        if scale.contains(&0.0) {
+            return Err(self.invalid(span.clone(), format!("{}.scale: must not be zero", field)));
+        }
+
+        let [x, y, z] = rotate.map(f32::to_radians);
+        let rotation =
+            Quat::from_rotation_z(z) * Quat::from_rotation_y(y) * Quat::from_rotation_x(x);
+        Ok((
+            Vec3::from_array(scale),
+            rotation,
+            Vec3::from_array(translate),
+        ))
+    }
+}
+
+fn vec3(v: [f32; 3]) -> Vector3 {
+    Point3::from_array(v)
+}
+
+/// 1-based line and column of the start of `span` in `source`.
+fn line_column(source: &str, span: Range<usize>) -> (usize, usize) {
+    let offset = span.start.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
@@ -0,0 +1,571 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use toml::Spanned;
+use toml::de::{DeTable, DeValue, ValueDeserializer};
+
+use crate::background::SkyConfig;
+use crate::color::ColorSpace;
+use crate::materials::ConductorPreset;
+use crate::tiles::TileOrder;
+
+/// Top level of a scene file, see `scenes/example.toml`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneDescription {
+    #[serde(default)]
+    pub render: RenderSettings,
+    pub camera: CameraSettings,
+    #[serde(default)]
+    pub background: BackgroundDescription,
+    #[serde(default)]
+    pub textures: BTreeMap<String, Spanned<TextureDescription>>,
+    #[serde(default)]
+    pub materials: BTreeMap<String, Spanned<MaterialDescription>>,
+    #[serde(default)]
+    pub objects: Vec<Spanned<ObjectDescription>>,
+    #[serde(default)]
+    pub lights: Vec<Spanned<LightDescription>>,
+}
+
+/// Malformed scene file, `span` is the byte range of the offending TOML.
+#[derive(Debug)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl SceneDescription {
+    /// Parses a scene file. Messages of errors inside a table are prefixed
+    /// with the path of the offending key, e.g. `objects[2].radius`.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        Self::parse_tagged(source).map_err(|mut err| {
+            if let Ok(root) = DeTable::parse(source)
+                && let Some((_, path)) = key_path(root.get_ref(), &err.span, "")
+            {
+                err.message = format!("{}: {}", path, err.message);
+            }
+            err
+        })
+    }
+
+    /// `#[serde(tag = "type")]` buffers the whole table before picking a
+    /// variant, which drops the position of the offending field, so tagged
+    /// tables are rewritten to serde's externally tagged form first.
+    fn parse_tagged(source: &str) -> Result<Self, ParseError> {
+        let mut root = DeTable::parse(source).map_err(ParseError::from)?;
+        let span = root.span();
+        let table = root.get_mut();
+
+        if let Some(background) = table.get_mut("background") {
+            untag(background)?;
+        }
+        for key in ["textures", "materials"] {
+            if let Some(entries) = table.get_mut(key)
+                && let DeValue::Table(entries) = entries.get_mut()
+            {
+                for (_, entry) in entries.iter_mut() {
+                    untag(entry)?;
+                }
+            }
+        }
+        for key in ["objects", "lights"] {
+            if let Some(entries) = table.get_mut(key)
+                && let DeValue::Array(entries) = entries.get_mut()
+            {
+                for entry in entries.iter_mut() {
+                    if key == "objects" {
+                        untag_object(entry)?;
+                    } else {
+                        untag(entry)?;
+                    }
+                }
+            }
+        }
+
+        let root = Spanned::new(span, DeValue::Table(root.into_inner()));
+        Self::deserialize(ValueDeserializer::from(root)).map_err(ParseError::from)
+    }
+}
+
+impl From<toml::de::Error> for ParseError {
+    fn from(err: toml::de::Error) -> Self {
+        ParseError {
+            span: err.span().unwrap_or(0..0),
+            message: err.message().to_string(),
+        }
+    }
+}
+
+/// Rewrites `{ type = "name", ... }` to `{ name = { ... } }`. Values other
+/// than tables are left for serde to report.
+fn untag(value: &mut Spanned<DeValue<'_>>) -> Result<(), ParseError> {
+    let span = value.span();
+    let DeValue::Table(table) = value.get_mut() else {
+        return Ok(());
+    };
+    let Some(tag) = table.remove("type") else {
+        return Err(ParseError {
+            span,
+            message: "missing field `type`".to_string(),
+        });
+    };
+    let tag_span = tag.span();
+    let DeValue::String(name) = tag.into_inner() else {
+        return Err(ParseError {
+            span: tag_span,
+            message: "invalid type: expected a string".to_string(),
+        });
+    };
+
+    let fields = std::mem::take(table);
+    table.insert(
+        Spanned::new(tag_span, name),
+        Spanned::new(span, DeValue::Table(fields)),
+    );
+    Ok(())
+}
+
+/// [`untag`] for objects, which nest further objects in `constant_medium`
+/// and `instance`.
+fn untag_object(value: &mut Spanned<DeValue<'_>>) -> Result<(), ParseError> {
+    if let DeValue::Table(fields) = value.get_mut() {
+        for key in ["boundary", "object"] {
+            if let Some(nested) = fields.get_mut(key) {
+                untag_object(nested)?;
+            }
+        }
+    }
+    untag(value)
+}
+
+/// Path of the innermost key whose name or value contains `span`, along with
+/// the length of the matched range. Table spans only cover their header, so
+/// every entry is searched rather than just the ones containing `span`.
+fn key_path(table: &DeTable<'_>, span: &Range<usize>, prefix: &str) -> Option<(usize, String)> {
+    table
+        .iter()
+        .filter_map(|(key, value)| {
+            let path = if prefix.is_empty() {
+                key.get_ref().to_string()
+            } else {
+                format!("{}.{}", prefix, key.get_ref())
+            };
+            if contains(&key.span(), span) {
+                Some((key.span().len(), path))
+            } else {
+                value_path(value, span, path)
+            }
+        })
+        .min_by_key(|(len, _)| *len)
+}
+
+fn value_path(
+    value: &Spanned<DeValue<'_>>,
+    span: &Range<usize>,
+    path: String,
+) -> Option<(usize, String)> {
+    let nested = match value.get_ref() {
+        DeValue::Table(table) => key_path(table, span, &path),
+        DeValue::Array(array) => array
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| value_path(item, span, format!("{}[{}]", path, i)))
+            .min_by_key(|(len, _)| *len),
+        _ => None,
+    };
+    nested.or_else(|| contains(&value.span(), span).then(|| (value.span().len(), path)))
+}
+
+fn contains(outer: &Range<usize>, inner: &Range<usize>) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenderSettings {
+    pub width: NonZeroU32,
+    /// Defaults to `width / aspect_ratio`.
+    pub height: Option<NonZeroU32>,
+    /// Defaults to `width / height` when `height` is set, 16:9 otherwise.
+    pub aspect_ratio: Option<Spanned<f32>>,
+    pub samples_per_pixel: NonZeroU32,
+    pub max_depth: i32,
+    pub light_intensity: f32,
+    pub threads: usize,
+    pub tile_size: Spanned<usize>,
+    /// `scanline`, `spiral` or `hilbert`.
+    pub tile_order: TileOrder,
+    pub seed: u64,
+    /// Working space colors are converted into: `linear_srgb`, `rec2020` or `acescg`.
+    pub color_space: ColorSpace,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            width: NonZeroU32::new(800).unwrap(),
+            height: None,
+            aspect_ratio: None,
+            samples_per_pixel: NonZeroU32::new(100).unwrap(),
+            max_depth: 50,
+            light_intensity: 0.95,
+            threads: 0,
+            tile_size: Spanned::new(0..0, 32),
+            tile_order: TileOrder::default(),
+            seed: 0,
+            color_space: ColorSpace::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraSettings {
+    pub look_from: [f32; 3],
+    pub look_at: [f32; 3],
+    #[serde(default = "default_fov")]
+    pub fov: f32,
+    #[serde(default)]
+    pub defocus_angle: f32,
+    #[serde(default = "default_focus_dist")]
+    pub focus_dist: f32,
+    #[serde(default)]
+    pub shutter_open: f32,
+    #[serde(default)]
+    pub shutter_close: f32,
+}
+
+fn default_fov() -> f32 {
+    20.0
+}
+
+fn default_focus_dist() -> f32 {
+    10.0
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum BackgroundDescription {
+    Solid {
+        color: [f32; 3],
+    },
+    Gradient {
+        #[serde(default = "default_horizon")]
+        horizon: [f32; 3],
+        #[serde(default = "default_zenith")]
+        zenith: [f32; 3],
+    },
+    /// Equirectangular `.hdr` or `.exr` image, importance sampled as a light.
+    Environment {
+        path: PathBuf,
+        /// Degrees about the vertical axis.
+        #[serde(default)]
+        rotation: f32,
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    /// Analytic daylight sky with a sun.
+    Sky(SkyDescription),
+}
+
+/// See [`crate::background::SkyConfig`] for the parameters.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SkyDescription {
+    pub sun_elevation: f32,
+    pub sun_azimuth: f32,
+    pub turbidity: f32,
+    pub sun_size: f32,
+    pub sun_intensity: f32,
+}
+
+impl Default for SkyDescription {
+    fn default() -> Self {
+        let config = SkyConfig::default();
+        Self {
+            sun_elevation: config.sun_elevation,
+            sun_azimuth: config.sun_azimuth,
+            turbidity: config.turbidity,
+            sun_size: config.sun_size,
+            sun_intensity: config.sun_intensity,
+        }
+    }
+}
+
+fn default_intensity() -> f32 {
+    1.0
+}
+
+impl Default for BackgroundDescription {
+    fn default() -> Self {
+        BackgroundDescription::Gradient {
+            horizon: default_horizon(),
+            zenith: default_zenith(),
+        }
+    }
+}
+
+fn default_horizon() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_zenith() -> [f32; 3] {
+    [0.5, 0.7, 1.0]
+}
+
+/// Either an inline color or the name of an entry in `[textures]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TextureRef {
+    Color([f32; 3]),
+    Named(String),
+}
+
+/// Either a number or the name of an entry in `[textures]`, whose first
+/// channel is used.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ScalarRef {
+    Value(f32),
+    Named(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum TextureDescription {
+    Solid {
+        color: [f32; 3],
+    },
+    Checker {
+        scale: f32,
+        even: TextureRef,
+        odd: TextureRef,
+    },
+    UvChecker {
+        columns: u32,
+        rows: u32,
+        even: TextureRef,
+        odd: TextureRef,
+    },
+    Image {
+        path: PathBuf,
+    },
+    Noise {
+        scale: f32,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum MaterialDescription {
+    Lambertian {
+        albedo: TextureRef,
+    },
+    Metal {
+        albedo: TextureRef,
+        #[serde(default)]
+        fuzz: f32,
+    },
+    Dielectric {
+        refraction_index: f32,
+    },
+    /// GGX metal, either a `preset` or explicit `eta` and `k`.
+    Conductor {
+        preset: Option<ConductorPreset>,
+        eta: Option<[f32; 3]>,
+        k: Option<[f32; 3]>,
+        #[serde(default)]
+        roughness: f32,
+    },
+    RoughDielectric {
+        refraction_index: f32,
+        roughness: f32,
+    },
+    Principled(Box<PrincipledDescription>),
+    /// Phase function for `constant_medium` objects.
+    Isotropic {
+        albedo: TextureRef,
+    },
+    DiffuseLight {
+        emit: TextureRef,
+    },
+}
+
+/// See [`crate::materials::PrincipledConfig`] for the parameters.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrincipledDescription {
+    pub base_color: TextureRef,
+    #[serde(default = "zero")]
+    pub metallic: ScalarRef,
+    #[serde(default = "half")]
+    pub roughness: ScalarRef,
+    #[serde(default = "half")]
+    pub specular: ScalarRef,
+    #[serde(default = "zero")]
+    pub sheen: ScalarRef,
+    #[serde(default = "zero")]
+    pub clearcoat: ScalarRef,
+    #[serde(default = "default_clearcoat_roughness")]
+    pub clearcoat_roughness: ScalarRef,
+    #[serde(default = "zero")]
+    pub transmission: ScalarRef,
+    #[serde(default = "default_ior")]
+    pub ior: ScalarRef,
+}
+
+fn zero() -> ScalarRef {
+    ScalarRef::Value(0.0)
+}
+
+fn half() -> ScalarRef {
+    ScalarRef::Value(0.5)
+}
+
+fn default_clearcoat_roughness() -> ScalarRef {
+    ScalarRef::Value(0.1)
+}
+
+fn default_ior() -> ScalarRef {
+    ScalarRef::Value(1.5)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ObjectDescription {
+    Sphere {
+        center: [f32; 3],
+        radius: f32,
+        material: String,
+        /// Center at time 1, the sphere moves linearly from `center` at time 0.
+        end_center: Option<[f32; 3]>,
+    },
+    Triangle {
+        vertices: [[f32; 3]; 3],
+        normals: Option<[[f32; 3]; 3]>,
+        uvs: Option<[[f32; 2]; 3]>,
+        material: String,
+    },
+    /// Parallelogram with corner `q` and edges `u` and `v`.
+    Quad {
+        q: [f32; 3],
+        u: [f32; 3],
+        v: [f32; 3],
+        material: String,
+    },
+    Disk {
+        center: [f32; 3],
+        normal: [f32; 3],
+        radius: f32,
+        material: String,
+    },
+    /// Infinite plane through `point`.
+    Plane {
+        point: [f32; 3],
+        normal: [f32; 3],
+        material: String,
+    },
+    /// Axis-aligned box made of six quads.
+    #[serde(rename = "box")]
+    Cuboid {
+        min: [f32; 3],
+        max: [f32; 3],
+        material: String,
+    },
+    /// Wavefront OBJ file, `material` is used for faces without an MTL entry.
+    Mesh { path: PathBuf, material: String },
+    /// Volume filling `boundary`, whose material must be `isotropic` and is
+    /// used as the phase function.
+    ConstantMedium {
+        boundary: Box<ObjectDescription>,
+        density: f32,
+    },
+    /// `object` scaled, then rotated by `rotate` degrees about the x, y and z
+    /// axes in that order, then translated.
+    Instance {
+        object: Box<ObjectDescription>,
+        #[serde(default)]
+        translate: [f32; 3],
+        #[serde(default)]
+        rotate: [f32; 3],
+        #[serde(default = "unit_scale")]
+        scale: [f32; 3],
+        /// Animation applied on top of the static transform.
+        #[serde(default)]
+        keyframes: Vec<KeyframeDescription>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeyframeDescription {
+    pub time: f32,
+    #[serde(default)]
+    pub translate: [f32; 3],
+    #[serde(default)]
+    pub rotate: [f32; 3],
+    #[serde(default = "unit_scale")]
+    pub scale: [f32; 3],
+}
+
+fn unit_scale() -> [f32; 3] {
+    [1.0; 3]
+}
+
+impl ObjectDescription {
+    pub fn material(&self) -> &str {
+        match self {
+            ObjectDescription::Sphere { material, .. }
+            | ObjectDescription::Triangle { material, .. }
+            | ObjectDescription::Quad { material, .. }
+            | ObjectDescription::Disk { material, .. }
+            | ObjectDescription::Plane { material, .. }
+            | ObjectDescription::Cuboid { material, .. }
+            | ObjectDescription::Mesh { material, .. } => material,
+            ObjectDescription::ConstantMedium { boundary, .. } => boundary.material(),
+            ObjectDescription::Instance { object, .. } => object.material(),
+        }
+    }
+}
+
+/// Delta lights, `color` is scaled by `intensity`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum LightDescription {
+    Point {
+        position: [f32; 3],
+        #[serde(default = "white")]
+        color: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    /// `angle` is the cone's half-angle in degrees, the light fades over the
+    /// last `falloff` degrees.
+    Spot {
+        position: [f32; 3],
+        direction: [f32; 3],
+        angle: f32,
+        #[serde(default)]
+        falloff: f32,
+        #[serde(default = "white")]
+        color: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    /// Parallel light travelling along `direction`.
+    Directional {
+        direction: [f32; 3],
+        #[serde(default = "white")]
+        color: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+}
+
+fn white() -> [f32; 3] {
+    [1.0; 3]
+}
@@ -0,0 +1,18 @@
+pub mod checker;
+pub mod image_texture;
+pub mod noise;
+pub mod perlin;
+pub mod solid_color;
+
+pub use checker::{CheckerTexture, UvCheckerTexture};
+pub use image_texture::ImageTexture;
+pub use noise::NoiseTexture;
+pub use perlin::Perlin;
+pub use solid_color::SolidColor;
+
+use crate::{color::Color, math::Point3};
+
+/// Color lookup evaluated at surface coordinates (u, v) and world position `p`.
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color;
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::math::Point3;
+
+use super::Texture;
+
+/// 3D checker pattern in world space, `scale` is the size of one cell.
+pub struct CheckerTexture {
+    inv_scale: f32,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: f32, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self {
+            inv_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color {
+        let cell = (*p * self.inv_scale).floor();
+        let sum = cell.x as i64 + cell.y as i64 + cell.z as i64;
+
+        if sum % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
+
+/// Checker pattern in texture space with `columns` x `rows` cells over the unit square.
+pub struct UvCheckerTexture {
+    columns: f32,
+    rows: f32,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl UvCheckerTexture {
+    pub fn new(columns: u32, rows: u32, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self {
+            columns: columns as f32,
+            rows: rows as f32,
+            even,
+            odd,
+        }
+    }
+}
+
+impl Texture for UvCheckerTexture {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color {
+        let sum = (u * self.columns).floor() as i64 + (v * self.rows).floor() as i64;
+
+        if sum % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
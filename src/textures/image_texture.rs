@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use image::{DynamicImage, ImageResult};
+
+use crate::color::{Color, ColorSpace, srgb_to_linear};
+use crate::math::Point3;
+
+use super::Texture;
+
+/// Texture backed by an image file, stored as linear colors.
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl ImageTexture {
+    /// Loads an image and converts it into the `space` working space.
+    ///
+    /// Float images (EXR, HDR) are taken as linear sRGB, everything else is
+    /// decoded with the sRGB transfer function. 16-bit images are decoded in
+    /// float to keep their precision.
+    pub fn load<P: AsRef<Path>>(path: P, space: ColorSpace) -> ImageResult<Self> {
+        let img = image::open(path)?;
+        let (width, height) = (img.width() as usize, img.height() as usize);
+
+        let pixels = match img {
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => img
+                .into_rgb32f()
+                .pixels()
+                .map(|px| space.from_srgb(Color::from_array(px.0)))
+                .collect(),
+            DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_) => img
+                .into_rgb32f()
+                .pixels()
+                .map(|px| space.from_srgb(Color::from_array(px.0.map(srgb_to_linear))))
+                .collect(),
+            _ => {
+                let table: Vec<f32> = (0..=255)
+                    .map(|i| srgb_to_linear(i as f32 / 255.0))
+                    .collect();
+
+                img.into_rgb8()
+                    .pixels()
+                    .map(|px| {
+                        let linear = Color::new(
+                            table[px[0] as usize],
+                            table[px[1] as usize],
+                            table[px[2] as usize],
+                        );
+                        space.from_srgb(linear)
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixel at column `x` and row `y`, counted from the top.
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _p: &Point3) -> Color {
+        if self.pixels.is_empty() {
+            return Color::new(0.0, 1.0, 1.0);
+        }
+
+        let u = u.clamp(0.0, 1.0);
+        let v = 1.0 - v.clamp(0.0, 1.0);
+
+        let x = ((u * self.width as f32) as usize).min(self.width - 1);
+        let y = ((v * self.height as f32) as usize).min(self.height - 1);
+
+        self.pixels[y * self.width + x]
+    }
+}
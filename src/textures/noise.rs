@@ -0,0 +1,26 @@
+use crate::color::Color;
+use crate::math::Point3;
+
+use super::{Perlin, Texture};
+
+/// Marble-like pattern: a sine along Z phase-shifted by Perlin turbulence.
+pub struct NoiseTexture {
+    noise: Perlin,
+    scale: f32,
+}
+
+impl NoiseTexture {
+    pub fn new(scale: f32) -> Self {
+        Self {
+            noise: Perlin::new(),
+            scale,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f32, _v: f32, p: &Point3) -> Color {
+        let phase = self.scale * p.z + 10.0 * self.noise.turb(p, 7);
+        Color::splat(0.5) * (1.0 + phase.sin())
+    }
+}
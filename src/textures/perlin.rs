@@ -0,0 +1,100 @@
+use crate::math::{Point3, Vector3, rand_rng, rand_rng_vec3};
+
+const POINT_COUNT: usize = 256;
+
+/// Gradient noise with trilinear-interpolated random unit vectors.
+pub struct Perlin {
+    rand_vec: Vec<Vector3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Perlin {
+    pub fn new() -> Self {
+        let rand_vec = (0..POINT_COUNT)
+            .map(|_| rand_rng_vec3(-1.0, 1.0).normalize())
+            .collect();
+
+        Self {
+            rand_vec,
+            perm_x: Perlin::generate_perm(),
+            perm_y: Perlin::generate_perm(),
+            perm_z: Perlin::generate_perm(),
+        }
+    }
+
+    /// Noise value in roughly [-1, 1].
+    pub fn noise(&self, p: &Point3) -> f32 {
+        let f = p.floor();
+        let (u, v, w) = (p.x - f.x, p.y - f.y, p.z - f.z);
+        let (i, j, k) = (f.x as i64, f.y as i64, f.z as i64);
+
+        let mut c = [[[Vector3::ZERO; 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    let idx = self.perm_x[((i + di as i64) & 255) as usize]
+                        ^ self.perm_y[((j + dj as i64) & 255) as usize]
+                        ^ self.perm_z[((k + dk as i64) & 255) as usize];
+                    *corner = self.rand_vec[idx];
+                }
+            }
+        }
+
+        Perlin::interp(&c, u, v, w)
+    }
+
+    /// Sum of `depth` octaves of absolute noise.
+    pub fn turb(&self, p: &Point3, depth: u32) -> f32 {
+        let mut accum = 0.0;
+        let mut temp_p = *p;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accum += weight * self.noise(&temp_p);
+            weight *= 0.5;
+            temp_p *= 2.0;
+        }
+
+        accum.abs()
+    }
+
+    fn generate_perm() -> Vec<usize> {
+        let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+        for i in (1..POINT_COUNT).rev() {
+            let target = (rand_rng(0.0, (i + 1) as f32) as usize).min(i);
+            p.swap(i, target);
+        }
+        p
+    }
+
+    fn interp(c: &[[[Vector3; 2]; 2]; 2], u: f32, v: f32, w: f32) -> f32 {
+        let uu = u * u * (3.0 - 2.0 * u);
+        let vv = v * v * (3.0 - 2.0 * v);
+        let ww = w * w * (3.0 - 2.0 * w);
+        let mut accum = 0.0;
+
+        for (i, plane) in c.iter().enumerate() {
+            for (j, row) in plane.iter().enumerate() {
+                for (k, corner) in row.iter().enumerate() {
+                    let (fi, fj, fk) = (i as f32, j as f32, k as f32);
+                    let weight = Vector3::new(u - fi, v - fj, w - fk);
+
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * corner.dot(weight);
+                }
+            }
+        }
+
+        accum
+    }
+}
@@ -0,0 +1,20 @@
+use crate::color::Color;
+use crate::math::Point3;
+
+use super::Texture;
+
+pub struct SolidColor {
+    albedo: Color,
+}
+
+impl SolidColor {
+    pub fn new(albedo: Color) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f32, _v: f32, _p: &Point3) -> Color {
+        self.albedo
+    }
+}
@@ -0,0 +1,117 @@
+use std::str::FromStr;
+
+use glam::Mat3A;
+
+use crate::color::{Color, ColorSpace, luminance};
+
+/// Curve compressing linear radiance into the displayable [0, 1] range.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ToneMapper {
+    /// Hard clamp, values above 1.0 are clipped.
+    #[default]
+    Clamp,
+    /// `L / (1 + L)` on luminance, preserving hue.
+    Reinhard,
+    /// Reinhard with luminance `white` mapping to 1.0.
+    ReinhardExtended { white: f32 },
+    /// Narkowicz's fit of the ACES filmic reference curve.
+    Aces,
+    /// AgX-style log encoding with a sigmoid contrast curve.
+    Agx,
+}
+
+impl FromStr for ToneMapper {
+    type Err = String;
+
+    /// Parses `clamp`, `reinhard`, `reinhard-extended`, `aces` or `agx`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "clamp" | "none" => Ok(ToneMapper::Clamp),
+            "reinhard" => Ok(ToneMapper::Reinhard),
+            "reinhard-extended" => Ok(ToneMapper::ReinhardExtended { white: 4.0 }),
+            "aces" => Ok(ToneMapper::Aces),
+            "agx" => Ok(ToneMapper::Agx),
+            other => Err(format!("unknown tone mapper `{}`", other)),
+        }
+    }
+}
+
+impl ToneMapper {
+    /// Maps linear radiance to linear display values in [0, 1].
+    pub fn apply(&self, color: Color) -> Color {
+        let color = color.max(Color::ZERO);
+
+        match *self {
+            ToneMapper::Clamp => color.min(Color::ONE),
+            ToneMapper::Reinhard => scale_luminance(color, |l| l / (1.0 + l)),
+            ToneMapper::ReinhardExtended { white } => {
+                scale_luminance(color, |l| l * (1.0 + l / (white * white)) / (1.0 + l))
+            }
+            ToneMapper::Aces => {
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                ((color * (a * color + b)) / (color * (c * color + d) + e))
+                    .clamp(Color::ZERO, Color::ONE)
+            }
+            ToneMapper::Agx => agx(color),
+        }
+    }
+}
+
+/// Exposure and tone curve applied when converting linear radiance for display.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisplayTransform {
+    /// Space the rendered radiance is in, converted to sRGB primaries first.
+    pub working_space: ColorSpace,
+    /// Exposure in stops, radiance is scaled by `2^exposure`.
+    pub exposure: f32,
+    pub tone_mapper: ToneMapper,
+}
+
+impl DisplayTransform {
+    pub fn apply(&self, color: Color) -> Color {
+        let color = self.working_space.to_srgb(color);
+        self.tone_mapper.apply(color * self.exposure.exp2())
+    }
+}
+
+fn scale_luminance(color: Color, curve: impl Fn(f32) -> f32) -> Color {
+    let l = luminance(color);
+    if l <= 0.0 {
+        return Color::ZERO;
+    }
+
+    (color * (curve(l) / l)).min(Color::ONE)
+}
+
+const AGX_MIN_EV: f32 = -12.47393;
+const AGX_MAX_EV: f32 = 4.026069;
+
+fn agx(color: Color) -> Color {
+    #[rustfmt::skip]
+    let inset = Mat3A::from_cols_array(&[
+        0.8424791, 0.04232824, 0.04237565,
+        0.0784336, 0.8784686, 0.0784336,
+        0.07922375, 0.07916613, 0.879143,
+    ]);
+    #[rustfmt::skip]
+    let outset = Mat3A::from_cols_array(&[
+        1.196879, -0.05289685, -0.05297164,
+        -0.09802088, 1.151903, -0.09804345,
+        -0.09902974, -0.09896118, 1.151074,
+    ]);
+
+    let v = inset * color;
+    let v = Color::new(v.x.log2(), v.y.log2(), v.z.log2())
+        .clamp(Color::splat(AGX_MIN_EV), Color::splat(AGX_MAX_EV));
+    let v = (v - AGX_MIN_EV) / (AGX_MAX_EV - AGX_MIN_EV);
+
+    let x2 = v * v;
+    let x4 = x2 * x2;
+    let v =
+        15.5 * x4 * x2 - 40.14 * x4 * v + 31.96 * x4 - 6.868 * x2 * v + 0.4298 * x2 + 0.1191 * v
+            - 0.00232;
+
+    // The curve output is display encoded, bring it back to linear.
+    let v = (outset * v).max(Color::ZERO);
+    Color::new(v.x.powf(2.2), v.y.powf(2.2), v.z.powf(2.2)).min(Color::ONE)
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use raytracer::color::Color;
+use raytracer::hittable::sphere::Sphere;
+use raytracer::hittable::{BvhNode, Hittable, HittableList, Plane};
+use raytracer::materials::{Lambertian, LambertianConfig, Material};
+use raytracer::math::{Interval, Point3, Ray, Vector3, rand_rng, rand_unit_vec3, seed_rng};
+use raytracer::textures::SolidColor;
+
+fn random_world(seed: u64, count: usize) -> HittableList {
+    seed_rng(seed);
+    let mat: Arc<dyn Material> = Arc::new(Lambertian::new(LambertianConfig {
+        albedo: Arc::new(SolidColor::new(Color::new(0.5, 0.5, 0.5))),
+    }));
+
+    let mut world = HittableList::new();
+    for _ in 0..count {
+        let center = Point3::new(
+            rand_rng(-20.0, 20.0),
+            rand_rng(-20.0, 20.0),
+            rand_rng(-20.0, 20.0),
+        );
+        world.add(Box::new(Sphere::new(
+            center,
+            rand_rng(0.1, 2.0),
+            Arc::clone(&mat),
+        )));
+    }
+
+    world
+}
+
+fn assert_same_hits(list: &dyn Hittable, bvh: &dyn Hittable, rays: usize) {
+    let ray_t = Interval::new(0.001, f32::INFINITY);
+
+    for _ in 0..rays {
+        let orig = Point3::new(
+            rand_rng(-30.0, 30.0),
+            rand_rng(-30.0, 30.0),
+            rand_rng(-30.0, 30.0),
+        );
+        let ray = Ray::new(orig, rand_unit_vec3());
+
+        match (list.hit(&ray, &ray_t), bvh.hit(&ray, &ray_t)) {
+            (None, None) => {}
+            (Some(a), Some(b)) => {
+                assert_eq!(a.t(), b.t());
+                assert_eq!(a.p(), b.p());
+                assert_eq!(a.normal(), b.normal());
+            }
+            (a, b) => panic!(
+                "linear list hit: {:?}, bvh hit: {:?}",
+                a.map(|h| h.t()),
+                b.map(|h| h.t())
+            ),
+        }
+    }
+}
+
+#[test]
+fn bvh_matches_linear_list_closest_hit() {
+    for (seed, count) in [(1, 1), (2, 2), (3, 7), (4, 100), (5, 500)] {
+        let list = random_world(seed, count);
+        let bvh = BvhNode::new(random_world(seed, count));
+
+        seed_rng(seed + 1000);
+        assert_same_hits(&list, &bvh, 2000);
+    }
+}
+
+#[test]
+fn bvh_handles_overlapping_and_coincident_objects() {
+    let mat: Arc<dyn Material> = Arc::new(Lambertian::new(LambertianConfig {
+        albedo: Arc::new(SolidColor::new(Color::new(0.5, 0.5, 0.5))),
+    }));
+    let build = || {
+        let mut world = HittableList::new();
+        for i in 0..16 {
+            let radius = 1.0 + (i % 4) as f32 * 0.25;
+            world.add(Box::new(Sphere::new(
+                Point3::ZERO,
+                radius,
+                Arc::clone(&mat),
+            )));
+        }
+        world
+    };
+
+    let list = build();
+    let bvh = BvhNode::new(build());
+
+    seed_rng(42);
+    assert_same_hits(&list, &bvh, 1000);
+}
+
+#[test]
+fn bvh_bounding_box_encloses_all_objects() {
+    let list = random_world(7, 200);
+    let bvh = BvhNode::new(random_world(7, 200));
+
+    let (a, b) = (list.bounding_box(), bvh.bounding_box());
+    assert_eq!(a.min(), b.min());
+    assert_eq!(a.max(), b.max());
+}
+
+#[test]
+fn bvh_keeps_unbounded_planes() {
+    let with_planes = |seed| {
+        let mut world = random_world(seed, 50);
+        let mat: Arc<dyn Material> = Arc::new(Lambertian::new(LambertianConfig {
+            albedo: Arc::new(SolidColor::new(Color::new(0.5, 0.5, 0.5))),
+        }));
+        world.add(Box::new(Plane::new(
+            Point3::new(0.0, -10.0, 0.0),
+            Vector3::Y,
+            Arc::clone(&mat),
+        )));
+        world.add(Box::new(Plane::new(
+            Point3::new(5.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.5, 0.2),
+            mat,
+        )));
+        world
+    };
+
+    let list = with_planes(9);
+    let bvh = BvhNode::new(with_planes(9));
+
+    seed_rng(1009);
+    assert_same_hits(&list, &bvh, 2000);
+}
+
+#[test]
+fn empty_bvh_never_hits() {
+    let bvh = BvhNode::new(HittableList::new());
+    let ray = Ray::new(Point3::ZERO, Vector3::X);
+
+    assert!(
+        bvh.hit(&ray, &Interval::new(0.001, f32::INFINITY))
+            .is_none()
+    );
+}
@@ -0,0 +1,76 @@
+use raytracer::math::{Distribution1D, Distribution2D};
+
+const STEPS: usize = 1000;
+
+fn uniforms() -> impl Iterator<Item = f32> {
+    (0..STEPS).map(|i| (i as f32 + 0.5) / STEPS as f32)
+}
+
+#[test]
+fn samples_report_the_density_of_their_segment() {
+    let func = vec![1.0, 0.0, 3.0, 0.0, 4.0];
+    let dist = Distribution1D::new(func.clone());
+    let n = func.len() as f32;
+    assert!((dist.integral() - 8.0 / n).abs() < 1e-6);
+
+    for u in uniforms().chain([0.0, 1.0]) {
+        let (x, pdf, index) = dist.sample(u);
+        assert!((0.0..1.0).contains(&x), "u = {}: x = {}", u, x);
+        assert_eq!(index, ((x * n) as usize).min(func.len() - 1));
+        assert!(
+            func[index] > 0.0,
+            "u = {} landed in empty segment {}",
+            u,
+            index
+        );
+        assert_eq!(pdf, dist.pdf(index));
+        assert!((pdf - func[index] / dist.integral()).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn zero_functions_are_sampled_uniformly() {
+    let dist = Distribution1D::new(vec![0.0; 4]);
+    assert_eq!(dist.integral(), 0.0);
+
+    for u in uniforms().chain([0.0, 1.0]) {
+        let (x, pdf, index) = dist.sample(u);
+        assert!((0.0..1.0).contains(&x), "u = {}: x = {}", u, x);
+        assert!((x - u).abs() < 1e-5, "u = {}: x = {}", u, x);
+        assert_eq!(index, (x * 4.0) as usize);
+        assert_eq!(pdf, 1.0);
+        assert_eq!(pdf, dist.pdf(index));
+    }
+}
+
+#[test]
+fn density_integrates_to_one() {
+    let dist = Distribution1D::new(vec![0.5, 2.0, 0.0, 1.5]);
+    let total: f32 = (0..dist.len()).map(|i| dist.pdf(i)).sum::<f32>() / dist.len() as f32;
+    assert!((total - 1.0).abs() < 1e-5, "{}", total);
+}
+
+#[test]
+fn grid_samples_report_their_density() {
+    let (width, height) = (4, 3);
+    let func: Vec<f32> = (0..width * height)
+        .map(|i| if i % 5 == 0 { 0.0 } else { i as f32 })
+        .collect();
+    let dist = Distribution2D::new(&func, width, height);
+
+    for u1 in uniforms().step_by(37) {
+        for u2 in uniforms().step_by(41) {
+            let (p, pdf) = dist.sample(u1, u2);
+            assert!((0.0..1.0).contains(&p.x) && (0.0..1.0).contains(&p.y));
+
+            let cell = (p.y * height as f32) as usize * width + (p.x * width as f32) as usize;
+            assert!(func[cell] > 0.0, "({}, {}) landed in an empty cell", u1, u2);
+            assert!(
+                (pdf - dist.pdf(p)).abs() < 1e-5,
+                "{} != {}",
+                pdf,
+                dist.pdf(p)
+            );
+        }
+    }
+}
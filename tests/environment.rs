@@ -0,0 +1,110 @@
+use std::f32::consts::PI;
+use std::fs;
+
+use image::{Rgb, Rgb32FImage};
+use raytracer::background::{Background, EnvironmentMap};
+use raytracer::color::{Color, ColorSpace};
+use raytracer::math::{Point3, Ray, Vector3, seed_rng};
+use raytracer::textures::ImageTexture;
+
+const WIDTH: u32 = 16;
+const HEIGHT: u32 = 8;
+
+/// Environment map built from a `WIDTH` × `HEIGHT` float image, turned by 30°.
+fn environment(name: &str, pixel: impl Fn(u32, u32) -> f32) -> EnvironmentMap {
+    let dir = std::env::temp_dir().join(format!(
+        "raytracer-environment-{}-{}",
+        name,
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("map.exr");
+    Rgb32FImage::from_fn(WIDTH, HEIGHT, |x, y| Rgb([pixel(x, y); 3]))
+        .save(&path)
+        .unwrap();
+    let image = ImageTexture::load(&path, ColorSpace::LinearSrgb).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    EnvironmentMap::new(image, 30.0, 1.0)
+}
+
+fn varied(x: u32, y: u32) -> f32 {
+    0.1 + ((x * 7 + y * 3) % 5) as f32 + if (x, y) == (3, 2) { 50.0 } else { 0.0 }
+}
+
+fn radiance(env: &EnvironmentMap, direction: Vector3) -> Color {
+    env.color(&Ray::new(Point3::ZERO, direction))
+}
+
+#[test]
+fn density_integrates_to_one_over_the_sphere() {
+    let env = environment("integral", varied);
+
+    let (steps_theta, steps_phi) = (400, 800);
+    let (d_theta, d_phi) = (PI / steps_theta as f32, 2.0 * PI / steps_phi as f32);
+    let mut total = 0.0;
+    for i in 0..steps_theta {
+        let theta = (i as f32 + 0.5) * d_theta;
+        for j in 0..steps_phi {
+            let phi = (j as f32 + 0.5) * d_phi;
+            let direction = Vector3::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            total += env.pdf_value(&direction) * theta.sin() * d_theta * d_phi;
+        }
+    }
+
+    assert!((total - 1.0).abs() < 0.01, "{}", total);
+}
+
+#[test]
+fn sampled_directions_match_their_density() {
+    let env = environment("estimate", varied);
+    seed_rng(7);
+
+    // E[1 / pdf] over sampled directions is the area of the sphere.
+    let samples = 100_000;
+    let mut estimate = 0.0;
+    for _ in 0..samples {
+        let direction = env.random();
+        assert!((direction.length() - 1.0).abs() < 1e-4);
+
+        let pdf = env.pdf_value(&direction);
+        assert!(pdf > 0.0, "sampled {} has no density", direction);
+        estimate += 1.0 / pdf;
+    }
+    estimate /= samples as f32;
+
+    assert!(
+        (estimate - 4.0 * PI).abs() < 0.02 * 4.0 * PI,
+        "{} != {}",
+        estimate,
+        4.0 * PI
+    );
+}
+
+#[test]
+fn sampled_directions_land_on_the_sampled_pixel() {
+    let env = environment("single", |x, y| if (x, y) == (5, 3) { 1.0 } else { 0.0 });
+    seed_rng(11);
+
+    // Every pixel but one is black, so each sampled direction has to map back
+    // into that pixel of the image.
+    for _ in 0..10_000 {
+        let direction = env.random();
+        assert_eq!(radiance(&env, direction), Color::ONE, "{}", direction);
+
+        let sin_theta = (1.0 - direction.y * direction.y).sqrt();
+        let expected = (WIDTH * HEIGHT) as f32 / (2.0 * PI * PI * sin_theta);
+        let pdf = env.pdf_value(&direction);
+        assert!(
+            (pdf - expected).abs() < 1e-3 * expected,
+            "{} != {}",
+            pdf,
+            expected
+        );
+    }
+}
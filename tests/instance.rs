@@ -0,0 +1,177 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use glam::{Affine3A, Quat, Vec3};
+use raytracer::color::Color;
+use raytracer::hittable::sphere::Sphere;
+use raytracer::hittable::{BvhNode, Hittable, HittableList, Instance, Keyframe, Triangle};
+use raytracer::materials::{Lambertian, LambertianConfig, Material};
+use raytracer::math::{Interval, Point3, Ray, Vector3};
+use raytracer::textures::SolidColor;
+
+fn material() -> Arc<dyn Material> {
+    Arc::new(Lambertian::new(LambertianConfig {
+        albedo: Arc::new(SolidColor::new(Color::splat(0.5))),
+    }))
+}
+
+fn keyframe(time: f32, degrees: f32) -> Keyframe {
+    Keyframe {
+        time,
+        scale: Vec3::ONE,
+        rotation: Quat::from_rotation_y(degrees.to_radians()),
+        translation: Vec3::ZERO,
+    }
+}
+
+#[test]
+fn rotating_instances_are_hit_between_bound_samples() {
+    // A small sphere 10 units from the pivot turning by 90°, offset by half
+    // a bounding step so that it lies on the x axis at time 1/32, where it
+    // reaches furthest past the poses sampled for the bounding box.
+    let half_step = 90.0 / 32.0;
+    let sphere = Sphere::new(Point3::new(10.0, 0.0, 0.0), 0.05, material());
+    let instance = Instance::keyframed(
+        Arc::new(sphere),
+        vec![keyframe(0.0, -half_step), keyframe(1.0, 90.0 - half_step)],
+    );
+
+    let mut list = HittableList::new();
+    list.add(Box::new(instance));
+    let bvh = BvhNode::new(list);
+
+    // Grazes the far side of the sphere, outside the sampled poses.
+    let ray = Ray::with_time(
+        Point3::new(10.045, 0.0, 5.0),
+        Vector3::new(0.0, 0.0, -1.0),
+        1.0 / 32.0,
+    );
+    let hit = bvh
+        .hit(&ray, &Interval::new(0.001, f32::INFINITY))
+        .expect("the instance's bounding box culled a real hit");
+    assert!((hit.p().x - 10.045).abs() < 1e-4);
+}
+
+/// Transforms with a non-uniform scale and with a mirroring negative one.
+fn stretched_transforms() -> [Affine3A; 2] {
+    let rotation = Quat::from_rotation_z(0.4) * Quat::from_rotation_y(0.7);
+    let translation = Vec3::new(1.0, -2.0, 0.5);
+    [
+        Affine3A::from_scale_rotation_translation(Vec3::new(3.0, 0.5, 1.5), rotation, translation),
+        Affine3A::from_scale_rotation_translation(
+            Vec3::new(-2.0, 1.0, 0.75),
+            rotation,
+            translation,
+        ),
+    ]
+}
+
+/// Rays through `target` from a ring of points around it.
+fn rays_towards(target: Point3) -> impl Iterator<Item = Ray> {
+    (0..12).map(move |i| {
+        let angle = i as f32 * 0.5;
+        let orig = target + 6.0 * Vector3::new(angle.cos(), 0.6 * angle.sin(), angle.sin());
+        Ray::new(orig, target - orig)
+    })
+}
+
+#[test]
+fn stretched_instances_hit_like_the_transformed_geometry() {
+    let vertices = [
+        Point3::new(-1.0, -1.0, 0.2),
+        Point3::new(1.0, -0.5, -0.3),
+        Point3::new(0.0, 1.0, 0.1),
+    ];
+
+    for transform in stretched_transforms() {
+        let instance = Instance::new(Arc::new(Triangle::new(vertices, material())), transform);
+        let transformed =
+            Triangle::new(vertices.map(|v| transform.transform_point3a(v)), material());
+        let centroid = (vertices[0] + vertices[1] + vertices[2]) / 3.0;
+        let target = transform.transform_point3a(centroid);
+
+        for ray in rays_towards(target) {
+            let ray_t = Interval::new(0.001, f32::INFINITY);
+            let expected = transformed.hit(&ray, &ray_t).unwrap();
+            let hit = instance.hit(&ray, &ray_t).unwrap();
+
+            assert!((hit.t() - expected.t()).abs() < 1e-4);
+            assert!((*hit.p() - *expected.p()).length() < 1e-4);
+            assert!((*hit.p() - ray.at(hit.t())).length() < 1e-4);
+
+            // Mirroring flips the winding, so only the line of the normal has
+            // to agree, while it still faces the ray.
+            assert!((hit.normal().length() - 1.0).abs() < 1e-5);
+            assert!(hit.normal().dot(*expected.normal()).abs() > 1.0 - 1e-4);
+            assert!(hit.normal().dot(*ray.dir()) < 0.0);
+        }
+    }
+}
+
+#[test]
+fn stretched_spheres_keep_outward_normals() {
+    let sphere = Arc::new(Sphere::new(Point3::ZERO, 1.0, material()));
+
+    for transform in stretched_transforms() {
+        let instance = Instance::new(sphere.clone(), transform);
+        let inverse = transform.inverse();
+
+        // Hit from outside, then from the center of the ellipsoid.
+        let center = transform.transform_point3a(Point3::ZERO);
+        let rays = rays_towards(center)
+            .chain(rays_towards(center).map(|ray| Ray::new(center, -*ray.dir())));
+        for (i, ray) in rays.enumerate() {
+            let hit = instance
+                .hit(&ray, &Interval::new(0.001, f32::INFINITY))
+                .unwrap();
+            let outside = i < 12;
+            assert_eq!(hit.front_face(), outside);
+
+            // The hit lies on the ellipsoid, whose outward gradient at `p`
+            // is the inverse transpose of the sphere's normal.
+            let object_p = inverse.transform_point3a(*hit.p());
+            assert!((object_p.length() - 1.0).abs() < 1e-4);
+            assert!((*hit.p() - ray.at(hit.t())).length() < 1e-4);
+
+            let outward = (inverse.matrix3.transpose() * object_p).normalize();
+            let normal = *hit.normal();
+            assert!((normal.length() - 1.0).abs() < 1e-5);
+            assert!(normal.dot(*ray.dir()) < 0.0);
+            let expected = if outside { outward } else { -outward };
+            assert!(
+                (normal - expected).length() < 1e-4,
+                "{} != {}",
+                normal,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn stretched_instances_bound_the_transformed_object() {
+    let sphere = Arc::new(Sphere::new(Point3::new(0.5, 0.0, -0.25), 1.0, material()));
+
+    for transform in stretched_transforms() {
+        let bbox = Instance::new(sphere.clone(), transform).bounding_box();
+        for i in 0..64 {
+            for j in 0..=32 {
+                let (phi, theta) = (i as f32 * PI / 32.0, j as f32 * PI / 32.0);
+                let local = Point3::new(0.5, 0.0, -0.25)
+                    + Vector3::new(
+                        theta.sin() * phi.cos(),
+                        theta.cos(),
+                        theta.sin() * phi.sin(),
+                    );
+                let p = transform.transform_point3a(local);
+
+                assert!(
+                    p.cmpge(bbox.min() - 1e-4).all() && p.cmple(bbox.max() + 1e-4).all(),
+                    "{} outside {:?}",
+                    p,
+                    bbox
+                );
+            }
+        }
+    }
+}
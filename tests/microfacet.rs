@@ -0,0 +1,200 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use raytracer::color::Color;
+use raytracer::hittable::HitRecord;
+use raytracer::materials::{
+    Conductor, ConductorConfig, Material, RoughDielectric, RoughDielectricConfig,
+};
+use raytracer::math::{Point3, Ray, Vector3, seed_rng};
+
+const SAMPLES: usize = 100_000;
+
+/// Hit at the origin of a surface facing +Z.
+fn hit(mat: Arc<dyn Material>, front_face: bool) -> HitRecord {
+    HitRecord::new(Point3::ZERO, Vector3::Z, 1.0, 0.0, 0.0, front_face, mat)
+}
+
+/// Ray arriving at the origin from `wo`, which has the cosine `cos` to +Z.
+fn incoming(cos: f32) -> (Ray, Vector3) {
+    let wo = Vector3::new((1.0 - cos * cos).sqrt(), 0.0, cos);
+    (Ray::new(wo, -wo), wo)
+}
+
+/// Conductor reflecting all light at every angle, with `eta = 0`, `k = 1`.
+fn white_conductor(roughness: f32) -> Arc<dyn Material> {
+    Arc::new(Conductor::new(ConductorConfig {
+        eta: Color::ZERO,
+        k: Color::ONE,
+        roughness,
+    }))
+}
+
+#[test]
+fn white_conductors_do_not_create_energy() {
+    seed_rng(3);
+
+    for roughness in [0.05, 0.2, 0.5, 1.0] {
+        let hit = hit(white_conductor(roughness), true);
+        for cos in [0.9, 0.5, 0.2] {
+            let (ray, wo) = incoming(cos);
+
+            let (mut mean, mut estimate) = (0.0, 0.0);
+            for _ in 0..SAMPLES {
+                let Some(scatter) = hit.mat().scatter(&ray, &hit) else {
+                    continue;
+                };
+                let weight = scatter.attenuation.x;
+                assert!(weight <= 1.0 + 1e-4, "weight {}", weight);
+                mean += weight;
+
+                // The weight has to agree with the BSDF and density that
+                // light sampling combines with it.
+                let wi = scatter.ray.dir().normalize();
+                let pdf = hit.mat().pdf(&hit, &wi, &wo);
+                assert!(pdf > 0.0);
+                estimate += hit.mat().bsdf_eval(&hit, &wi, &wo).x / pdf;
+            }
+            mean /= SAMPLES as f32;
+            estimate /= SAMPLES as f32;
+
+            let context = format!("roughness {}, cos {}", roughness, cos);
+            assert!(mean <= 1.0, "{}: {}", context, mean);
+            assert!(
+                (mean - estimate).abs() < 1e-3,
+                "{}: {} != {}",
+                context,
+                mean,
+                estimate
+            );
+            if roughness <= 0.05 {
+                assert!(mean > 0.99, "{}: {}", context, mean);
+            }
+        }
+    }
+}
+
+#[test]
+fn smooth_white_conductors_are_perfect_mirrors() {
+    let hit = hit(white_conductor(0.0), true);
+    let (ray, wo) = incoming(0.6);
+
+    let scatter = hit.mat().scatter(&ray, &hit).unwrap();
+    assert!(scatter.pdf.is_none());
+    assert!((scatter.attenuation - Color::ONE).abs().max_element() < 1e-5);
+
+    let mirrored = Vector3::new(-wo.x, -wo.y, wo.z);
+    assert!((scatter.ray.dir().normalize() - mirrored).length() < 1e-5);
+}
+
+/// Integral of `mat.pdf` over the hemispheres above and below the surface.
+fn pdf_integrals(hit: &HitRecord, wo: &Vector3) -> (f32, f32) {
+    let (steps_cos, steps_phi) = (800, 800);
+    let (d_cos, d_phi) = (2.0 / steps_cos as f32, 2.0 * PI / steps_phi as f32);
+
+    let (mut above, mut below) = (0.0, 0.0);
+    for i in 0..steps_cos {
+        let cos = -1.0 + (i as f32 + 0.5) * d_cos;
+        let sin = (1.0 - cos * cos).sqrt();
+        for j in 0..steps_phi {
+            let phi = (j as f32 + 0.5) * d_phi;
+            let wi = Vector3::new(sin * phi.cos(), sin * phi.sin(), cos);
+            let p = hit.mat().pdf(hit, &wi, wo) * d_cos * d_phi;
+            if cos > 0.0 {
+                above += p;
+            } else {
+                below += p;
+            }
+        }
+    }
+
+    (above, below)
+}
+
+fn rough_glass(roughness: f32, front_face: bool) -> HitRecord {
+    let mat = Arc::new(RoughDielectric::new(RoughDielectricConfig {
+        refraction_index: 1.5,
+        roughness,
+    }));
+    hit(mat, front_face)
+}
+
+/// Fractions of `scatter` calls that reflect and that transmit.
+fn scatter_fractions(hit: &HitRecord, ray: &Ray) -> (f32, f32) {
+    let (mut reflected, mut transmitted) = (0, 0);
+    for _ in 0..SAMPLES {
+        match hit.mat().scatter(ray, hit) {
+            Some(scatter) if scatter.ray.dir().z > 0.0 => reflected += 1,
+            Some(_) => transmitted += 1,
+            None => {}
+        }
+    }
+
+    (
+        reflected as f32 / SAMPLES as f32,
+        transmitted as f32 / SAMPLES as f32,
+    )
+}
+
+#[test]
+fn rough_dielectric_reflection_and_transmission_sum_to_one() {
+    seed_rng(5);
+
+    // Entering and leaving the glass, the latter with total internal
+    // reflection past the critical angle.
+    for front_face in [true, false] {
+        let hit = rough_glass(0.1, front_face);
+        for cos in [0.9, 0.5, 0.2] {
+            let (ray, _) = incoming(cos);
+            let (reflected, transmitted) = scatter_fractions(&hit, &ray);
+
+            let context = format!("front face {}, cos {}", front_face, cos);
+            assert!(transmitted > 0.0, "{}", context);
+            assert!(
+                (reflected + transmitted - 1.0).abs() < 2e-3,
+                "{}: {} + {}",
+                context,
+                reflected,
+                transmitted
+            );
+        }
+    }
+
+    // Fresnel reflectance of glass at normal incidence.
+    let (ray, _) = incoming(1.0);
+    let (reflected, _) = scatter_fractions(&rough_glass(0.1, true), &ray);
+    assert!((reflected - 0.04).abs() < 3e-3, "{}", reflected);
+}
+
+#[test]
+fn rough_dielectric_density_matches_its_lobe_choice() {
+    seed_rng(9);
+
+    // Rougher glass loses the microfacet normals that send `wi` to the wrong
+    // side of the surface, but what remains has to integrate to the same
+    // fractions `pdf` reports.
+    for front_face in [true, false] {
+        let hit = rough_glass(0.5, front_face);
+        for cos in [0.9, 0.5] {
+            let (ray, wo) = incoming(cos);
+            let (reflected, transmitted) = scatter_fractions(&hit, &ray);
+            let (above, below) = pdf_integrals(&hit, &wo);
+
+            let context = format!("front face {}, cos {}", front_face, cos);
+            assert!(
+                (above - reflected).abs() < 0.01,
+                "{}: {} != {}",
+                context,
+                above,
+                reflected
+            );
+            assert!(
+                (below - transmitted).abs() < 0.01,
+                "{}: {} != {}",
+                context,
+                below,
+                transmitted
+            );
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use image::{Rgb, RgbImage};
+use raytracer::color::{Color, ColorSpace};
+use raytracer::hittable::{HitRecord, Hittable};
+use raytracer::loaders::load_obj;
+use raytracer::materials::{Lambertian, LambertianConfig, Material};
+use raytracer::math::{Interval, Point3, Ray, Vector3};
+use raytracer::textures::SolidColor;
+
+const MTL: &str = "\
+newmtl light
+Kd 0.8 0.8 0.8
+Ke 4 4 4
+
+newmtl dissolved
+Kd 1 1 1
+d 0.5
+Ni 1.33
+
+newmtl refractive
+illum 7
+
+newmtl pbr_metal
+Kd 0.9 0.5 0.1
+Pm 1
+Pr 0
+
+newmtl mirror
+Ks 0.2 0.4 0.6
+Ns 1000
+illum 3
+
+newmtl matte
+Kd 0.3 0.6 0.9
+
+newmtl faded
+Kd 0.2 0.4 0.6
+d 0.9
+
+newmtl textured
+map_Kd -s 1 1 -clamp on -mm 0 1 grey texture.png
+";
+
+/// One face per material along +x, each facing +z. Face 0 is a quad.
+const OBJ: &str = "\
+mtllib test.mtl
+vt 0.25
+vt 0.1 0.2
+vt 0.5 0.75 0.0
+
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+f 1 2 3 4
+
+usemtl light
+v 2 0 0
+v 3 0 0
+v 2 1 0
+f 5 6 7
+
+usemtl dissolved
+v 4 0 0
+v 5 0 0
+v 4 1 0
+f -3 -2 -1
+
+usemtl refractive
+v 6 0 0
+v 7 0 0
+v 6 1 0
+f 11 12 13
+
+usemtl pbr_metal
+v 8 0 0
+v 9 0 0
+v 8 1 0
+f 14/2 15/2 16/2
+
+usemtl mirror
+v 10 0 0
+v 11 0 0
+v 10 1 0
+f 17/3 18/3 19/3
+
+usemtl matte
+v 12 0 0
+v 13 0 0
+v 12 1 0
+vn 0 0 1
+f 20/1/1 21/1/1 22/1/1
+
+usemtl missing
+v 14 0 0
+v 15 0 0
+v 14 1 0
+f 23 24 25
+
+usemtl faded
+v 16 0 0
+v 17 0 0
+v 16 1 0
+f 26 27 28
+
+usemtl textured
+v 18 0 0
+v 19 0 0
+v 18 1 0
+f 29 30 31
+";
+
+/// Writes the test OBJ and MTL into a fresh directory and loads them.
+fn load(name: &str, default_material: Arc<dyn Material>) -> impl Hittable {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("raytracer-obj-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("test.obj"), OBJ).unwrap();
+    fs::write(dir.join("test.mtl"), MTL).unwrap();
+    RgbImage::from_pixel(1, 1, Rgb([255, 255, 255]))
+        .save(dir.join("grey texture.png"))
+        .unwrap();
+
+    let mesh = load_obj(
+        dir.join("test.obj"),
+        default_material,
+        ColorSpace::LinearSrgb,
+    );
+    fs::remove_dir_all(&dir).unwrap();
+    let mesh = mesh.unwrap();
+    assert_eq!(mesh.triangle_count(), 11);
+    mesh
+}
+
+fn default_material() -> Arc<dyn Material> {
+    Arc::new(Lambertian::new(LambertianConfig {
+        albedo: Arc::new(SolidColor::new(Color::splat(0.5))),
+    }))
+}
+
+/// Hits face `index` head-on from +z.
+fn hit_face(mesh: &impl Hittable, index: usize) -> (Ray, HitRecord) {
+    let ray = Ray::new(
+        Point3::new(2.0 * index as f32 + 0.25, 0.25, 1.0),
+        Vector3::new(0.0, 0.0, -1.0),
+    );
+    let hit = mesh
+        .hit(&ray, &Interval::new(0.001, f32::INFINITY))
+        .unwrap_or_else(|| panic!("face {} missed", index));
+    (ray, hit)
+}
+
+fn assert_close(a: Color, b: Color) {
+    assert!((a - b).abs().max_element() < 1e-5, "{} != {}", a, b);
+}
+
+#[test]
+fn faces_without_a_known_material_use_the_default() {
+    let default = default_material();
+    let mesh = load("default", Arc::clone(&default));
+
+    for face in [0, 7] {
+        let (_, hit) = hit_face(&mesh, face);
+        assert!(Arc::ptr_eq(hit.mat(), &default), "face {}", face);
+    }
+}
+
+#[test]
+fn texture_coordinates_accept_one_to_three_components() {
+    let mesh = load("uvs", default_material());
+
+    for (face, uv) in [(4, (0.1, 0.2)), (5, (0.5, 0.75)), (6, (0.25, 0.0))] {
+        let (_, hit) = hit_face(&mesh, face);
+        assert!(
+            (hit.u() - uv.0).abs() < 1e-5 && (hit.v() - uv.1).abs() < 1e-5,
+            "face {}: ({}, {})",
+            face,
+            hit.u(),
+            hit.v()
+        );
+    }
+}
+
+#[test]
+fn emissive_materials_become_lights() {
+    let mesh = load("light", default_material());
+
+    let (ray, hit) = hit_face(&mesh, 1);
+    assert_close(hit.mat().emitted(&hit), Color::splat(4.0));
+    assert!(hit.mat().scatter(&ray, &hit).is_none());
+}
+
+#[test]
+fn dissolve_and_refraction_illum_become_dielectrics() {
+    let mesh = load("dielectric", default_material());
+
+    for face in [2, 3] {
+        let (ray, hit) = hit_face(&mesh, face);
+        let scatter = hit.mat().scatter(&ray, &hit).unwrap();
+        assert!(scatter.pdf.is_none(), "face {}", face);
+        assert_close(scatter.attenuation, Color::ONE);
+    }
+}
+
+#[test]
+fn metallic_and_reflection_illum_become_metals() {
+    let mesh = load("metal", default_material());
+
+    let (ray, hit) = hit_face(&mesh, 4);
+    let scatter = hit.mat().scatter(&ray, &hit).unwrap();
+    assert!(scatter.pdf.is_none());
+    assert_close(scatter.attenuation, Color::new(0.9, 0.5, 0.1));
+    assert!((scatter.ray.dir().normalize() - Vector3::Z).length() < 1e-5);
+
+    let (ray, hit) = hit_face(&mesh, 5);
+    let scatter = hit.mat().scatter(&ray, &hit).unwrap();
+    assert!(scatter.pdf.is_none());
+    assert_close(scatter.attenuation, Color::new(0.2, 0.4, 0.6));
+}
+
+#[test]
+fn other_materials_are_lambertian() {
+    let mesh = load("lambertian", default_material());
+
+    let (ray, hit) = hit_face(&mesh, 6);
+    let scatter = hit.mat().scatter(&ray, &hit).unwrap();
+    assert!(scatter.pdf.is_some());
+    assert_close(scatter.attenuation, Color::new(0.3, 0.6, 0.9));
+    assert_close(hit.mat().emitted(&hit), Color::ZERO);
+
+    // Dissolve without an index of refraction only fades the material.
+    let (ray, hit) = hit_face(&mesh, 8);
+    let scatter = hit.mat().scatter(&ray, &hit).unwrap();
+    assert!(scatter.pdf.is_some());
+    assert_close(scatter.attenuation, Color::new(0.2, 0.4, 0.6));
+}
+
+#[test]
+fn texture_options_are_skipped_before_the_file_name() {
+    let mesh = load("texture", default_material());
+
+    let (ray, hit) = hit_face(&mesh, 9);
+    let scatter = hit.mat().scatter(&ray, &hit).unwrap();
+    assert_close(scatter.attenuation, Color::ONE);
+}
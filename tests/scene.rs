@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use raytracer::scene::{Scene, SceneError};
+
+const HEADER: &str = r#"
+[camera]
+look_from = [0.0, 0.0, 0.0]
+look_at = [0.0, 0.0, -1.0]
+
+[materials.grey]
+type = "lambertian"
+albedo = [0.5, 0.5, 0.5]
+"#;
+
+/// Line, column and message of the syntax error in `objects`.
+fn syntax_error(objects: &str) -> (usize, usize, String) {
+    let source = format!("{}{}", HEADER, objects);
+    match Scene::parse(&source, Path::new("test.toml")) {
+        Err(SceneError::Syntax {
+            line,
+            column,
+            message,
+            ..
+        }) => (line, column, message),
+        Err(err) => panic!("expected a syntax error, got {}", err),
+        Ok(_) => panic!("expected a syntax error"),
+    }
+}
+
+#[test]
+fn errors_point_at_the_offending_field() {
+    let (line, column, message) = syntax_error(
+        r#"
+[[objects]]
+type = "sphere"
+center = [0.0, 0.0, -1.0]
+radius = "x"
+material = "grey"
+"#,
+    );
+    assert_eq!((line, column), (13, 10));
+    assert!(
+        message.starts_with("objects[0].radius: invalid type"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn errors_in_nested_objects_name_the_full_path() {
+    let (line, _, message) = syntax_error(
+        r#"
+[[objects]]
+type = "sphere"
+center = [0.0, 0.0, -1.0]
+radius = 0.5
+material = "grey"
+
+[[objects]]
+type = "instance"
+translate = [1.0, 0.0, 0.0]
+object = { type = "sphere", center = [0.0, "up", 0.0], radius = 1.0, material = "grey" }
+"#,
+    );
+    assert_eq!(line, 19);
+    assert!(
+        message.starts_with("objects[1].object.center[1]: invalid type"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn unknown_types_and_fields_are_reported_at_their_key() {
+    let (line, _, message) = syntax_error(
+        r#"
+[[objects]]
+type = "spere"
+"#,
+    );
+    assert_eq!(line, 11);
+    assert!(
+        message.starts_with("objects[0].type: unknown variant `spere`"),
+        "{}",
+        message
+    );
+
+    let (line, _, message) = syntax_error(
+        r#"
+[[objects]]
+type = "sphere"
+center = [0.0, 0.0, -1.0]
+radius = 0.5
+material = "grey"
+fuzz = 0.1
+"#,
+    );
+    assert_eq!(line, 15);
+    assert!(message.starts_with("objects[0].fuzz:"), "{}", message);
+}
+
+#[test]
+fn constant_medium_requires_an_isotropic_boundary() {
+    let source = format!(
+        "{}{}",
+        HEADER,
+        r#"
+[[objects]]
+type = "constant_medium"
+density = 0.5
+boundary = { type = "sphere", center = [0.0, 0.0, -1.0], radius = 0.5, material = "grey" }
+"#
+    );
+    match Scene::parse(&source, Path::new("test.toml")) {
+        Err(SceneError::Invalid { line, message, .. }) => {
+            assert_eq!(line, 10);
+            assert!(message.contains("not an isotropic material"), "{}", message);
+        }
+        Err(err) => panic!("expected an invalid scene error, got {}", err),
+        Ok(_) => panic!("expected an invalid scene error"),
+    }
+}
+
+#[test]
+fn zero_tile_size_is_rejected() {
+    let source = format!("[render]\ntile_size = 0\n{}", HEADER);
+    match Scene::parse(&source, Path::new("test.toml")) {
+        Err(SceneError::Invalid { line, message, .. }) => {
+            assert_eq!(line, 2);
+            assert!(message.starts_with("render.tile_size:"), "{}", message);
+        }
+        Err(err) => panic!("expected an invalid scene error, got {}", err),
+        Ok(_) => panic!("expected an invalid scene error"),
+    }
+}
@@ -0,0 +1,46 @@
+use raytracer::tiles::{TileOrder, tiles};
+
+const ORDERS: [TileOrder; 3] = [TileOrder::Scanline, TileOrder::Spiral, TileOrder::Hilbert];
+
+#[test]
+fn tiles_cover_every_pixel_once() {
+    for order in ORDERS {
+        for (width, height, size) in [(1, 1, 16), (100, 37, 16), (64, 64, 8), (33, 90, 7)] {
+            let mut covered = vec![0u32; width * height];
+            for tile in tiles(width, height, size, order) {
+                assert!(tile.width > 0 && tile.height > 0);
+                assert!(tile.width <= size && tile.height <= size);
+                for y in tile.y..tile.y + tile.height {
+                    for x in tile.x..tile.x + tile.width {
+                        covered[y * width + x] += 1;
+                    }
+                }
+            }
+
+            assert!(
+                covered.iter().all(|&n| n == 1),
+                "{:?} {}x{} tile {}",
+                order,
+                width,
+                height,
+                size
+            );
+        }
+    }
+}
+
+#[test]
+fn spiral_starts_at_the_center() {
+    let tiles = tiles(160, 160, 32, TileOrder::Spiral);
+    assert_eq!((tiles[0].x, tiles[0].y), (64, 64));
+}
+
+#[test]
+fn hilbert_steps_between_adjacent_tiles() {
+    let tiles = tiles(256, 256, 32, TileOrder::Hilbert);
+    for pair in tiles.windows(2) {
+        let dx = pair[0].x.abs_diff(pair[1].x);
+        let dy = pair[0].y.abs_diff(pair[1].y);
+        assert_eq!(dx + dy, 32);
+    }
+}